@@ -1,9 +1,14 @@
 use std::fmt::Write;
 
 use proc_macro::TokenStream;
+use proc_macro2::TokenTree;
 use quote::quote;
 use structmeta::StructMeta;
-use syn::{Expr, ExprLit, FnArg, ItemFn, Lit, LitInt, LitStr};
+use syn::{
+    Expr, ExprLit, FnArg, GenericArgument, ItemFn, Lit, LitStr, PathArguments, ReturnType, Type,
+};
+
+use crate::response::ResponseArgs;
 
 pub enum Method {
     Get,
@@ -22,11 +27,16 @@ struct ParamArgs {
     skip: bool,
 }
 
+#[derive(StructMeta, Default)]
+struct RouteArgs {
+    skip_responses: bool,
+}
+
 #[derive(StructMeta)]
-struct ResponseArgs {
-    status: Option<LitInt>,
-    description: Option<LitStr>,
+struct HeaderArgs {
+    name: LitStr,
     model: Option<syn::Path>,
+    optional: bool,
 }
 
 pub fn route(method: Method, args: TokenStream, mut item: TokenStream) -> TokenStream {
@@ -63,7 +73,9 @@ pub fn route(method: Method, args: TokenStream, mut item: TokenStream) -> TokenS
     };
 
     let mut doc = String::new();
+    let mut headers = vec![];
     let mut responses = vec![];
+    let mut args = RouteArgs::default();
 
     for attr in &input.attrs {
         if attr.path().is_ident("doc") {
@@ -80,37 +92,51 @@ pub fn route(method: Method, args: TokenStream, mut item: TokenStream) -> TokenS
 
             writeln!(doc, "{val}").unwrap();
         } else if attr.path().is_ident("response") {
-            let args = attr.parse_args::<ResponseArgs>().unwrap();
-            let status = args
-                .status
-                .map(|v| v.base10_parse().unwrap())
-                .unwrap_or(200u16);
+            let response_spec = match attr
+                .parse_args::<ResponseArgs>()
+                .and_then(ResponseArgs::response_spec)
+            {
+                Ok(response_spec) => response_spec,
+                Err(e) => {
+                    item.extend(TokenStream::from(e.into_compile_error()));
+                    return item;
+                }
+            };
 
-            let description = match args.description {
-                None => quote! { None },
-                Some(description) => {
-                    quote! { Some(#description.into()) }
+            responses.push(response_spec);
+        } else if attr.path().is_ident("speq") {
+            args = match attr.parse_args() {
+                Ok(args) => args,
+                Err(e) => {
+                    item.extend(TokenStream::from(e.into_compile_error()));
+                    return item;
+                }
+            };
+        } else if attr.path().is_ident("header") {
+            let args = match attr.parse_args::<HeaderArgs>() {
+                Ok(args) => args,
+                Err(e) => {
+                    item.extend(TokenStream::from(e.into_compile_error()));
+                    return item;
                 }
             };
 
+            let name = args.name;
             let type_desc = match args.model {
-                None => {
-                    quote! { None }
-                }
+                None => quote! { None },
                 Some(model) => {
-                    quote! { Some(<#model as speq::reflection::Reflect>::reflect(cx)) }
+                    quote! { Some(cx.reflect_as::<#model>(speq::reflection::Direction::Input)) }
                 }
             };
+            let is_optional = args.optional;
 
-            let response_spec = quote! {
-                speq::ResponseSpec {
-                    status: axum::http::StatusCode::from_u16(#status).unwrap(),
-                    description: #description,
+            headers.push(quote! {
+                speq::HeaderSpec {
+                    name: #name.into(),
                     type_desc: #type_desc,
+                    is_optional: #is_optional,
                 }
-            };
-
-            responses.push(response_spec);
+            });
         }
     }
 
@@ -138,13 +164,41 @@ pub fn route(method: Method, args: TokenStream, mut item: TokenStream) -> TokenS
         .map(|param| {
             let ty = &param.ty;
             quote! {
-                <#ty as speq::RouteHandlerInput>::describe(&mut input_cx, &mut spec);
+                <#ty as speq::RouteHandlerInput>::describe(
+                    &mut speq::RouteHandlerInputContext::new(cx),
+                    &mut spec,
+                );
             }
         })
         .collect::<Vec<_>>();
 
+    // Responses are inferred from the return type, unless inference is turned off. Types that
+    // are opaque or don't implement `RouteHandlerOutput` are left undescribed.
+    let output = match &input.sig.output {
+        _ if args.skip_responses => quote! {},
+        ReturnType::Type(_, ty) => {
+            let describers = output_types(ty)
+                .into_iter()
+                .filter(|ty| !contains_impl(quote! { #ty }))
+                .map(|ty| {
+                    quote! {
+                        (&&speq::axum::OutputDescriber::<#ty>(std::marker::PhantomData)).describe_output(cx, &mut spec);
+                    }
+                });
+            quote! {
+                {
+                    use speq::axum::{DescribeOutput as _, DescribeOutputFallback as _};
+                    #(#describers)*
+                }
+            }
+        }
+        ReturnType::Default => quote! {
+            <() as speq::RouteHandlerOutput>::describe(cx, &mut spec);
+        },
+    };
+
     input.attrs.retain(|attr| {
-        ["response"]
+        ["header", "response", "speq"]
             .iter()
             .all(|ident| !attr.path().is_ident(ident))
     });
@@ -177,14 +231,17 @@ pub fn route(method: Method, args: TokenStream, mut item: TokenStream) -> TokenS
                     method: #method,
                     src_file: file!().into(),
                     doc: #doc,
-                    headers: vec![],
+                    headers: vec![#(#headers),*],
                     query: None,
                     request: None,
-                    responses: vec![#(#responses),*],
+                    responses: vec![],
                 };
 
-                let mut input_cx = speq::RouteHandlerInputContext::new(cx);
+                #output
+                spec.override_responses([#(#responses),*]);
 
+                // Each input gets its own context, so that one being optional doesn't make the
+                // others optional.
                 #(#inputs)*
 
                 spec
@@ -199,3 +256,35 @@ pub fn route(method: Method, args: TokenStream, mut item: TokenStream) -> TokenS
         };
     })
 }
+
+/// Splits a `Result` into its success and error types, so that its success responses are still
+/// described when its error type doesn't implement `RouteHandlerOutput`.
+fn output_types(ty: &Type) -> Vec<&Type> {
+    if let Type::Path(path) = ty {
+        if let Some(segment) = path.path.segments.last() {
+            if let PathArguments::AngleBracketed(args) = &segment.arguments {
+                let types = args
+                    .args
+                    .iter()
+                    .filter_map(|arg| match arg {
+                        GenericArgument::Type(ty) => Some(ty),
+                        _ => None,
+                    })
+                    .collect::<Vec<_>>();
+                if segment.ident == "Result" && !types.is_empty() {
+                    return types;
+                }
+            }
+        }
+    }
+
+    vec![ty]
+}
+
+fn contains_impl(tokens: proc_macro2::TokenStream) -> bool {
+    tokens.into_iter().any(|token| match token {
+        TokenTree::Ident(ident) => ident == "impl",
+        TokenTree::Group(group) => contains_impl(group.stream()),
+        _ => false,
+    })
+}
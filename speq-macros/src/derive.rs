@@ -1,16 +1,299 @@
+use std::collections::BTreeSet;
+
 use proc_macro::TokenStream;
 use quote::quote;
-use syn::DeriveInput;
+use serde_derive_internals::{ast as serde_ast, attr as serde_attr, Derive};
+use structmeta::StructMeta;
+use syn::meta::ParseNestedMeta;
+use syn::parse::Parse;
+use syn::{
+    parse_quote, Attribute, DeriveInput, Expr, ExprLit, GenericParam, Lit, LitStr, Meta, Token,
+};
+
+#[derive(StructMeta, Default)]
+struct ContainerArgs {
+    format: Option<LitStr>,
+    name: Option<LitStr>,
+    remote: Option<LitStr>,
+    #[struct_meta(name = "type")]
+    ty: Option<syn::Type>,
+    schema_with: Option<syn::Path>,
+    example: Option<Expr>,
+    example_with: Option<syn::Path>,
+    example_default: bool,
+}
+
+#[derive(StructMeta, Default)]
+struct FieldArgs {
+    format: Option<LitStr>,
+    #[struct_meta(name = "type")]
+    ty: Option<syn::Type>,
+    /// A mirror of the field's remote type, as with `#[serde(with = "...")]`.
+    with: Option<syn::Type>,
+    min: Option<Expr>,
+    max: Option<Expr>,
+    pattern: Option<LitStr>,
+    schema_with: Option<syn::Path>,
+    example: Option<Expr>,
+    example_with: Option<syn::Path>,
+    example_default: bool,
+}
+
+/// Mirrors `speq::reflection::Direction`. Declarations built for no direction describe both.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Direction {
+    Input,
+    Output,
+}
+
+fn is_skipped(
+    direction: Option<Direction>,
+    skip_serializing: bool,
+    skip_deserializing: bool,
+) -> bool {
+    match direction {
+        None => skip_serializing && skip_deserializing,
+        Some(Direction::Input) => skip_deserializing,
+        Some(Direction::Output) => skip_serializing,
+    }
+}
+
+/// Whether every field or variant of a container is skipped in both directions.
+fn has_no_members(container: &serde_ast::Container) -> bool {
+    match &container.data {
+        serde_ast::Data::Enum(variants) => variants.iter().all(|variant| {
+            is_skipped(
+                None,
+                variant.attrs.skip_serializing(),
+                variant.attrs.skip_deserializing(),
+            )
+        }),
+        serde_ast::Data::Struct(_, fields) => fields.iter().all(|field| {
+            is_skipped(
+                None,
+                field.attrs.skip_serializing(),
+                field.attrs.skip_deserializing(),
+            )
+        }),
+    }
+}
+
+fn serde_name(direction: Option<Direction>, name: &serde_attr::Name) -> &str {
+    match direction {
+        Some(Direction::Input) => name.deserialize_name(),
+        _ => name.serialize_name(),
+    }
+}
 
 pub fn derive_reflect(input: TokenStream) -> TokenStream {
-    use serde_derive_internals::{ast as serde_ast, attr as serde_attr, Derive};
     let input = syn::parse_macro_input!(input as DeriveInput);
+    expand(&input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+fn expand(input: &DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
     let cx = serde_derive_internals::Ctxt::new();
-    let container = serde_ast::Container::from_ast(&cx, &input, Derive::Serialize).unwrap();
-    cx.check().unwrap();
+    let container = serde_ast::Container::from_ast(&cx, input, Derive::Serialize);
+    cx.check()?;
+    let container = container.unwrap();
+    let args: ContainerArgs = parse_args(&input.attrs)?;
+
+    let ident = &container.ident;
 
-    let ident = container.ident;
-    let expr = match container.data {
+    // A mirror of a remote type stands in for the remote type, so it takes on its id and name.
+    let remote = match &args.remote {
+        Some(remote) => Some(remote.parse::<syn::Path>()?),
+        None => container.attrs.remote().cloned(),
+    };
+    let example = match &remote {
+        Some(remote) => {
+            let (_, ty_generics, _) = container.generics.split_for_impl();
+            example(
+                &args.example,
+                &args.example_with,
+                args.example_default,
+                quote! { #remote #ty_generics },
+            )?
+        }
+        None => example(
+            &args.example,
+            &args.example_with,
+            args.example_default,
+            quote! { Self },
+        )?,
+    };
+
+    // Most types read and write the same way, but when serde attributes make the directions
+    // differ, the declaration depends on the direction the type is being reflected in.
+    let both = build_decl(&container, &input.attrs, &args, &example, None)?;
+    let read = build_decl(
+        &container,
+        &input.attrs,
+        &args,
+        &example,
+        Some(Direction::Input),
+    )?;
+    let write = build_decl(
+        &container,
+        &input.attrs,
+        &args,
+        &example,
+        Some(Direction::Output),
+    )?;
+    let expr = if read.to_string() == both.to_string() && write.to_string() == both.to_string() {
+        both
+    } else {
+        quote! {
+            match cx.direction() {
+                None => #both,
+                Some(Direction::Input) => #read,
+                Some(Direction::Output) => #write,
+            }
+        }
+    };
+
+    let mut generics = container.generics.clone();
+    let mut type_args = vec![];
+    let mut name_args = vec![];
+    for param in &container.generics.params {
+        match param {
+            GenericParam::Type(param) => {
+                let param = &param.ident;
+                generics
+                    .make_where_clause()
+                    .predicates
+                    .push(parse_quote!(#param: Reflect));
+                type_args.push(quote! { SpeqStr::from(std::any::type_name::<#param>()) });
+                name_args.push(quote! { <#param as Reflect>::type_name() });
+            }
+            GenericParam::Const(param) => {
+                let param = &param.ident;
+                type_args.push(quote! { SpeqStr::from(#param.to_string()) });
+                name_args.push(quote! { SpeqStr::from(#param.to_string()) });
+            }
+            GenericParam::Lifetime(_) => {}
+        }
+    }
+
+    let name = match (&args.name, &remote) {
+        (Some(name), _) => name.value(),
+        (None, Some(remote)) => remote.segments.last().unwrap().ident.to_string(),
+        (None, None) => ident.to_string(),
+    };
+
+    // Each instantiation of a generic type gets its own id (and so its own declaration), named
+    // after its type arguments.
+    let type_name = if name_args.is_empty() {
+        quote! { #name.into() }
+    } else {
+        quote! {
+            format!(
+                "{}<{}>",
+                #name,
+                [#(#name_args),*].join(", "),
+            ).into()
+        }
+    };
+
+    let type_id = if let Some(remote) = &remote {
+        let (_, ty_generics, _) = container.generics.split_for_impl();
+        quote! { std::any::type_name::<#remote #ty_generics>().into() }
+    } else if type_args.is_empty() {
+        quote! { concat!(module_path!(), "::", stringify!(#ident)).into() }
+    } else {
+        quote! {
+            format!(
+                "{}::{}<{}>",
+                module_path!(),
+                stringify!(#ident),
+                [#(#type_args),*].join(", "),
+            ).into()
+        }
+    };
+
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    Ok(quote! {
+        const _: () = {
+            use speq::reflection::*;
+            use speq::SpeqStr;
+            impl #impl_generics Reflect for #ident #ty_generics #where_clause {
+                fn type_id() -> Option<SpeqStr> {
+                    Some(#type_id)
+                }
+
+                fn type_name() -> SpeqStr {
+                    #type_name
+                }
+
+                fn reflect(cx: &mut TypeContext) -> Type {
+                    let id = Self::type_id().unwrap();
+                    let id = cx.insert_with(id, |cx| #expr);
+                    Type::Id(id)
+                }
+            }
+        };
+    })
+}
+
+fn build_decl(
+    container: &serde_ast::Container,
+    attrs: &[Attribute],
+    args: &ContainerArgs,
+    example: &proc_macro2::TokenStream,
+    direction: Option<Direction>,
+) -> syn::Result<proc_macro2::TokenStream> {
+    let doc = parse_doc(attrs);
+    let overridden = override_type(&args.ty, &args.schema_with)?;
+    // A type converted to or from another for serialization takes on that type's schema. Types
+    // with `into` are described by what they serialize as unless reflected as input. Without a
+    // direction, types only converted from another are still written as themselves, so they are
+    // only described by what they convert from if they have nothing of their own to describe.
+    let conversion = match direction {
+        None => container.attrs.type_into().or_else(|| {
+            has_no_members(container)
+                .then(|| {
+                    container
+                        .attrs
+                        .type_from()
+                        .or(container.attrs.type_try_from())
+                })
+                .flatten()
+        }),
+        Some(Direction::Input) => container
+            .attrs
+            .type_from()
+            .or(container.attrs.type_try_from()),
+        Some(Direction::Output) => container.attrs.type_into(),
+    };
+
+    let expr = match &container.data {
+        _ if overridden.is_some() => {
+            let mut ty = overridden.unwrap();
+            if let Some(format) = &args.format {
+                ty = quote! { #ty.with_format(#format.into()) };
+            }
+            quote! {
+                TypeDecl::Alias(AliasType {
+                    name: Self::type_name(),
+                    doc: #doc,
+                    example: #example,
+                    type_desc: #ty,
+                })
+            }
+        }
+        _ if conversion.is_some() => {
+            let ty = conversion.unwrap();
+            quote! {
+                TypeDecl::Alias(AliasType {
+                    name: Self::type_name(),
+                    doc: #doc,
+                    example: #example,
+                    type_desc: <#ty as Reflect>::reflect(cx),
+                })
+            }
+        }
         serde_ast::Data::Enum(variants) => {
             let tag = match container.attrs.tag() {
                 serde_attr::TagType::External => quote! { Some(EnumTag::External) },
@@ -23,89 +306,416 @@ pub fn derive_reflect(input: TokenStream) -> TokenStream {
                 serde_attr::TagType::None => quote! { None },
             };
 
-            let variants = variants.into_iter().map(|variant| {
-                let name = variant.ident.to_string();
-                let serialize_name = variant.attrs.name().serialize_name();
-                let kind = match variant.style {
-                    serde_ast::Style::Struct => {
-                        let fields = variant.fields.into_iter().map(build_field);
-                        quote! {
-                            EnumVariantKind::Struct(vec![#(#fields),*])
+            let variants = variants
+                .iter()
+                .filter(|variant| {
+                    !is_skipped(
+                        direction,
+                        variant.attrs.skip_serializing(),
+                        variant.attrs.skip_deserializing(),
+                    )
+                })
+                .map(|variant| {
+                    let name = variant.ident.to_string();
+                    let doc = parse_doc(&variant.original.attrs);
+                    let tag_value = serde_name(direction, variant.attrs.name());
+                    let aliases = aliases(direction, variant.attrs.aliases(), tag_value);
+                    // Unknown tags are only mapped to the variant when reading.
+                    let other = variant.attrs.other() && direction != Some(Direction::Output);
+                    let kind = match variant.style {
+                        serde_ast::Style::Struct => {
+                            let fields = build_fields(&variant.fields, false, direction)?;
+                            quote! {
+                                EnumVariantKind::Struct(vec![#(#fields),*])
+                            }
                         }
-                    }
-                    serde_ast::Style::Tuple => todo!("tuple enum"),
-                    serde_ast::Style::Newtype => {
-                        let ty = variant.fields[0].ty;
-                        quote! {
-                            EnumVariantKind::NewType(<#ty as Reflect>::reflect(cx))
+                        serde_ast::Style::Tuple => {
+                            let types = tuple_types(&variant.fields, direction)?;
+                            quote! {
+                                EnumVariantKind::Tuple(vec![#(#types),*])
+                            }
                         }
-                    }
-                    serde_ast::Style::Unit => quote! {
-                        EnumVariantKind::Unit
-                    },
-                };
-                quote! {
-                    EnumVariant {
-                        name: #name.into(),
-                        tag_value: #serialize_name.into(),
-                        kind: #kind,
-                    }
-                }
-            });
+                        serde_ast::Style::Newtype => {
+                            let ty = field_type(&variant.fields[0])?;
+                            quote! {
+                                EnumVariantKind::NewType(#ty)
+                            }
+                        }
+                        serde_ast::Style::Unit => quote! {
+                            EnumVariantKind::Unit
+                        },
+                    };
+                    Ok(quote! {
+                        EnumVariant {
+                            name: #name.into(),
+                            doc: #doc,
+                            tag_value: #tag_value.into(),
+                            aliases: vec![#(#aliases),*],
+                            other: #other,
+                            kind: #kind,
+                        }
+                    })
+                })
+                .collect::<syn::Result<Vec<_>>>()?;
 
             quote! {
                 TypeDecl::Enum(EnumType {
-                    name: stringify!(#ident).into(),
+                    name: Self::type_name(),
+                    doc: #doc,
+                    example: #example,
                     tag: #tag,
                     variants: vec![#(#variants),*],
                 })
             }
         }
+        serde_ast::Data::Struct(_, fields) if container.attrs.transparent() => {
+            let field = fields
+                .iter()
+                .find(|field| field.attrs.transparent())
+                .unwrap();
+            let mut ty = field_type(field)?;
+            if let Some(format) = &args.format {
+                ty = quote! { #ty.with_format(#format.into()) };
+            }
+            quote! {
+                TypeDecl::Alias(AliasType {
+                    name: Self::type_name(),
+                    doc: #doc,
+                    example: #example,
+                    type_desc: #ty,
+                })
+            }
+        }
         serde_ast::Data::Struct(style, fields) => match style {
             serde_ast::Style::Struct => {
-                let fields = fields.into_iter().map(build_field);
+                let default = !container.attrs.default().is_none();
+                let fields = build_fields(fields, default, direction)?;
                 quote! {
                     TypeDecl::Struct(StructType {
-                        name: stringify!(#ident).into(),
+                        name: Self::type_name(),
+                        doc: #doc,
+                        example: #example,
                         fields: vec![#(#fields),*],
                     })
                 }
             }
-            serde_ast::Style::Tuple => todo!(),
-            serde_ast::Style::Newtype => todo!(),
-            serde_ast::Style::Unit => todo!(),
+            serde_ast::Style::Tuple => {
+                let types = tuple_types(fields, direction)?;
+                quote! {
+                    TypeDecl::Alias(AliasType {
+                        name: Self::type_name(),
+                        doc: #doc,
+                        example: #example,
+                        type_desc: Type::Tuple(vec![#(#types),*]),
+                    })
+                }
+            }
+            serde_ast::Style::Newtype => {
+                let mut ty = field_type(&fields[0])?;
+                if let Some(format) = &args.format {
+                    ty = quote! { #ty.with_format(#format.into()) };
+                }
+                quote! {
+                    TypeDecl::Alias(AliasType {
+                        name: Self::type_name(),
+                        doc: #doc,
+                        example: #example,
+                        type_desc: #ty,
+                    })
+                }
+            }
+            serde_ast::Style::Unit => quote! {
+                TypeDecl::Alias(AliasType {
+                    name: Self::type_name(),
+                    doc: #doc,
+                    example: #example,
+                    type_desc: Type::Tuple(vec![]),
+                })
+            },
         },
     };
 
-    TokenStream::from(quote! {
-        const _: () = {
-            use speq::reflection::*;
-            impl Reflect for #ident {
-                fn type_id() -> Option<std::borrow::Cow<'static, str>> {
-                    Some(concat!(module_path!(), "::", stringify!(#ident)).into())
-                }
+    Ok(expr)
+}
 
-                fn reflect(cx: &mut TypeContext) -> Type {
-                    let id = Self::type_id().unwrap();
-                    cx.insert_with(id.clone(), |cx| #expr);
-                    Type::Id(id)
+/// Parses the arguments of all `#[speq(...)]` attributes in `attrs` as a single list.
+fn parse_args<T: Parse + Default>(attrs: &[Attribute]) -> syn::Result<T> {
+    let mut lists = vec![];
+    for attr in attrs {
+        if attr.path().is_ident("speq") {
+            lists.push(&attr.meta.require_list()?.tokens);
+        }
+    }
+
+    if lists.is_empty() {
+        Ok(T::default())
+    } else {
+        syn::parse2(quote! { #(#lists),* })
+    }
+}
+
+/// Collects the lines of any doc comments in `attrs`, returning an expression for the resulting
+/// `Option<SpeqStr>`.
+fn parse_doc(attrs: &[Attribute]) -> proc_macro2::TokenStream {
+    let lines = attrs
+        .iter()
+        .filter_map(|attr| match &attr.meta {
+            Meta::NameValue(meta) if meta.path.is_ident("doc") => match &meta.value {
+                Expr::Lit(ExprLit {
+                    lit: Lit::Str(str), ..
+                }) => Some(str.value().trim().to_owned()),
+                _ => None,
+            },
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+
+    let doc = lines.join("\n");
+    let doc = doc.trim();
+    if doc.is_empty() {
+        quote! { None }
+    } else {
+        quote! { Some(#doc.into()) }
+    }
+}
+
+/// Returns an expression reflecting the type given by `#[speq(type = ...)]` or
+/// `#[speq(schema_with = ...)]`, if either is present.
+fn override_type(
+    ty: &Option<syn::Type>,
+    schema_with: &Option<syn::Path>,
+) -> syn::Result<Option<proc_macro2::TokenStream>> {
+    match (ty, schema_with) {
+        (Some(ty), None) => Ok(Some(quote! { <#ty as Reflect>::reflect(cx) })),
+        (None, Some(schema_with)) => Ok(Some(quote! { #schema_with(cx) })),
+        (Some(ty), Some(_)) => Err(syn::Error::new_spanned(
+            ty,
+            "`type` and `schema_with` cannot be used together",
+        )),
+        (None, None) => Ok(None),
+    }
+}
+
+/// Returns an expression for the example given by `#[speq(example = ...)]`,
+/// `#[speq(example_with = ...)]` or `#[speq(example_default)]`, as an
+/// `Option<serde_json::Value>`. `ty` is the type whose default value is the example.
+fn example(
+    example: &Option<Expr>,
+    example_with: &Option<syn::Path>,
+    example_default: bool,
+    ty: proc_macro2::TokenStream,
+) -> syn::Result<proc_macro2::TokenStream> {
+    let value = match (example, example_with, example_default) {
+        (Some(example), None, false) => quote! { #example },
+        (None, Some(example_with), false) => quote! { #example_with() },
+        (None, None, true) => quote! { <#ty as Default>::default() },
+        (None, None, false) => return Ok(quote! { None }),
+        _ => {
+            return Err(syn::Error::new(
+                proc_macro2::Span::call_site(),
+                "only one of `example`, `example_with` and `example_default` can be used",
+            ))
+        }
+    };
+    Ok(quote! { speq::serde_json::to_value(&(#value)).ok() })
+}
+
+/// The constraints given by a `validator` crate `#[validate(...)]` attribute that can be
+/// described. Others, such as custom validation functions, are ignored.
+#[derive(Default)]
+struct Validate {
+    min: Option<Expr>,
+    max: Option<Expr>,
+    format: Option<&'static str>,
+}
+
+fn parse_validate(attrs: &[Attribute]) -> syn::Result<Validate> {
+    let mut validate = Validate::default();
+    for attr in attrs {
+        if !attr.path().is_ident("validate") {
+            continue;
+        }
+
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("length") || meta.path.is_ident("range") {
+                meta.parse_nested_meta(|meta| {
+                    if meta.path.is_ident("min") {
+                        validate.min = Some(meta.value()?.parse()?);
+                    } else if meta.path.is_ident("max") {
+                        validate.max = Some(meta.value()?.parse()?);
+                    } else if meta.path.is_ident("equal") {
+                        let value: Expr = meta.value()?.parse()?;
+                        validate.min = Some(value.clone());
+                        validate.max = Some(value);
+                    } else {
+                        skip_meta(&meta)?;
+                    }
+                    Ok(())
+                })
+            } else {
+                if meta.path.is_ident("email") {
+                    validate.format = Some("email");
+                } else if meta.path.is_ident("url") {
+                    validate.format = Some("uri");
                 }
+                skip_meta(&meta)
             }
-        };
-    })
+        })?;
+    }
+    Ok(validate)
 }
 
-fn build_field(field: serde_derive_internals::ast::Field) -> proc_macro2::TokenStream {
-    let name = field.attrs.name().serialize_name();
+fn skip_meta(meta: &ParseNestedMeta) -> syn::Result<()> {
+    if meta.input.peek(Token![=]) {
+        meta.value()?.parse::<Expr>()?;
+    } else if !meta.input.is_empty() && !meta.input.peek(Token![,]) {
+        meta.input.parse::<proc_macro2::TokenTree>()?;
+    }
+    Ok(())
+}
+
+fn field_type(field: &serde_ast::Field) -> syn::Result<proc_macro2::TokenStream> {
+    let args: FieldArgs = parse_args(&field.original.attrs)?;
+    let validate = parse_validate(&field.original.attrs)?;
     let ty = field.ty;
+    if let (Some(_), Some(with)) = (&args.ty, &args.with) {
+        return Err(syn::Error::new_spanned(
+            with,
+            "`type` and `with` cannot be used together",
+        ));
+    }
+
+    let mut type_desc = override_type(&args.ty.or(args.with), &args.schema_with)?
+        .unwrap_or_else(|| quote! { <#ty as Reflect>::reflect(cx) });
+    if let Some(format) = args
+        .format
+        .map(|format| format.value())
+        .or(validate.format.map(Into::into))
+    {
+        type_desc = quote! { #type_desc.with_format(#format.into()) };
+    }
+    Ok(type_desc)
+}
+
+/// Builds the fields of a struct or struct variant, leaving out those which serde skips in
+/// `direction`. `default` is whether the container has `#[serde(default)]`.
+fn build_fields(
+    fields: &[serde_ast::Field],
+    default: bool,
+    direction: Option<Direction>,
+) -> syn::Result<Vec<proc_macro2::TokenStream>> {
+    fields
+        .iter()
+        .filter(|field| {
+            !is_skipped(
+                direction,
+                field.attrs.skip_serializing(),
+                field.attrs.skip_deserializing(),
+            )
+        })
+        .map(|field| build_field(field, default, direction))
+        .collect()
+}
+
+/// Returns the types of the elements of a tuple struct or variant. Without a direction, these
+/// are the elements as serialized.
+fn tuple_types(
+    fields: &[serde_ast::Field],
+    direction: Option<Direction>,
+) -> syn::Result<Vec<proc_macro2::TokenStream>> {
+    fields
+        .iter()
+        .filter(|field| match direction {
+            Some(Direction::Input) => !field.attrs.skip_deserializing(),
+            _ => !field.attrs.skip_serializing(),
+        })
+        .map(field_type)
+        .collect()
+}
+
+/// Returns the names a field or variant is also deserialized from.
+fn aliases(
+    direction: Option<Direction>,
+    aliases: &BTreeSet<String>,
+    name: &str,
+) -> Vec<proc_macro2::TokenStream> {
+    if direction == Some(Direction::Output) {
+        return vec![];
+    }
+
+    aliases
+        .iter()
+        .filter(|alias| *alias != name)
+        .map(|alias| quote! { #alias.into() })
+        .collect()
+}
+
+fn build_field(
+    field: &serde_ast::Field,
+    default: bool,
+    direction: Option<Direction>,
+) -> syn::Result<proc_macro2::TokenStream> {
+    let name = serde_name(direction, field.attrs.name());
+    let doc = parse_doc(&field.original.attrs);
+    let aliases = aliases(direction, field.attrs.aliases(), name);
     let flatten = field.attrs.flatten();
-    let required = field.attrs.default().is_none();
-    quote! {
+    let has_default = !field.attrs.default().is_none() || default;
+    let (required, read_only, write_only) = match direction {
+        // Fields which are only ever written are always present in output, while others may be
+        // left out of input if they have a default.
+        None => {
+            let read_only = field.attrs.skip_deserializing();
+            let write_only = field.attrs.skip_serializing();
+            let required = !write_only
+                && field.attrs.skip_serializing_if().is_none()
+                && (read_only || !has_default);
+            (required, read_only, write_only)
+        }
+        Some(Direction::Input) => (!has_default, false, false),
+        Some(Direction::Output) => (field.attrs.skip_serializing_if().is_none(), false, false),
+    };
+    let type_desc = field_type(field)?;
+
+    // Constraints given to speq take precedence over those for the validator crate.
+    let args: FieldArgs = parse_args(&field.original.attrs)?;
+    let validate = parse_validate(&field.original.attrs)?;
+    let min = args
+        .min
+        .or(validate.min)
+        .map(|min| quote! { Some((#min) as f64) });
+    let max = args
+        .max
+        .or(validate.max)
+        .map(|max| quote! { Some((#max) as f64) });
+    let pattern = args.pattern.map(|pattern| quote! { Some(#pattern.into()) });
+    let none = || quote! { None };
+    let min = min.unwrap_or_else(none);
+    let max = max.unwrap_or_else(none);
+    let pattern = pattern.unwrap_or_else(none);
+    let ty = field.ty;
+    let example = example(
+        &args.example,
+        &args.example_with,
+        args.example_default,
+        quote! { #ty },
+    )?;
+
+    Ok(quote! {
         Field {
             name: #name.into(),
+            doc: #doc,
+            aliases: vec![#(#aliases),*],
             flatten: #flatten,
             required: #required,
-            type_desc: <#ty as Reflect>::reflect(cx),
+            read_only: #read_only,
+            write_only: #write_only,
+            constraints: Constraints {
+                min: #min,
+                max: #max,
+                pattern: #pattern,
+            },
+            example: #example,
+            type_desc: #type_desc,
         }
-    }
+    })
 }
@@ -3,12 +3,18 @@ use proc_macro::TokenStream;
 #[cfg(feature = "axum")]
 mod axum;
 mod derive;
+mod response;
 
-#[proc_macro_derive(Reflect, attributes(serde))]
+#[proc_macro_derive(Reflect, attributes(serde, speq))]
 pub fn derive_reflect(input: TokenStream) -> TokenStream {
     derive::derive_reflect(input)
 }
 
+#[proc_macro_derive(RouteHandlerOutput, attributes(response, speq))]
+pub fn derive_route_handler_output(input: TokenStream) -> TokenStream {
+    response::derive_route_handler_output(input)
+}
+
 macro_rules! axum_route_macro {
     ($name:ident, $method:ident) => {
         #[cfg(feature = "axum")]
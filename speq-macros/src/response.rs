@@ -0,0 +1,149 @@
+use proc_macro::TokenStream;
+use quote::quote;
+use structmeta::StructMeta;
+use syn::{Attribute, Data, DeriveInput, Expr, Fields, LitInt, LitStr};
+
+#[derive(StructMeta)]
+pub struct ResponseArgs {
+    status: Option<LitInt>,
+    description: Option<LitStr>,
+    model: Option<syn::Path>,
+    media_type: Option<LitStr>,
+    example: Option<Expr>,
+}
+
+#[derive(StructMeta, Default)]
+struct VariantArgs {
+    /// Whether the variant's single field describes the responses, as with `#[error(transparent)]`.
+    transparent: bool,
+}
+
+impl ResponseArgs {
+    /// Returns an expression for the `ResponseSpec` described by the arguments, reflecting the
+    /// model in `cx`.
+    pub fn response_spec(self) -> syn::Result<proc_macro2::TokenStream> {
+        let status = match &self.status {
+            Some(status) => {
+                let value = status.base10_parse::<u16>()?;
+                if !(100..=999).contains(&value) {
+                    return Err(syn::Error::new_spanned(
+                        status,
+                        "status codes must be between 100 and 999",
+                    ));
+                }
+                value
+            }
+            None => 200,
+        };
+
+        let description = match self.description {
+            None => quote! { None },
+            Some(description) => quote! { Some(#description.into()) },
+        };
+
+        // Models are serialized as JSON unless another media type is given.
+        let media_type = match (self.media_type, &self.model) {
+            (Some(media_type), _) => quote! { Some(#media_type.into()) },
+            (None, Some(_)) => quote! { Some("application/json".into()) },
+            (None, None) => quote! { None },
+        };
+
+        let type_desc = match self.model {
+            None => quote! { None },
+            Some(model) => {
+                quote! { Some(cx.reflect_as::<#model>(speq::reflection::Direction::Output)) }
+            }
+        };
+
+        let example = match self.example {
+            None => quote! { None },
+            Some(example) => quote! { speq::serde_json::to_value(&(#example)).ok() },
+        };
+
+        Ok(quote! {
+            speq::ResponseSpec {
+                status: Some(speq::StatusCode::from_u16(#status).unwrap()),
+                description: #description,
+                type_desc: #type_desc,
+                media_type: #media_type,
+                example: #example,
+            }
+        })
+    }
+}
+
+pub fn derive_route_handler_output(input: TokenStream) -> TokenStream {
+    let input = syn::parse_macro_input!(input as DeriveInput);
+    expand(&input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+fn expand(input: &DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    let mut describe = responses(&input.attrs)?;
+
+    if let Data::Enum(data) = &input.data {
+        for variant in &data.variants {
+            describe.extend(responses(&variant.attrs)?);
+
+            let mut args = VariantArgs::default();
+            for attr in &variant.attrs {
+                if attr.path().is_ident("speq") {
+                    args = attr.parse_args()?;
+                }
+            }
+
+            if args.transparent {
+                let field = match &variant.fields {
+                    Fields::Named(fields) if fields.named.len() == 1 => &fields.named[0],
+                    Fields::Unnamed(fields) if fields.unnamed.len() == 1 => &fields.unnamed[0],
+                    _ => {
+                        return Err(syn::Error::new_spanned(
+                            variant,
+                            "transparent variants must have exactly one field",
+                        ))
+                    }
+                };
+                let ty = &field.ty;
+                describe.push(quote! {
+                    <#ty as speq::RouteHandlerOutput>::describe(cx, route);
+                });
+            }
+        }
+    }
+
+    let ident = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics speq::RouteHandlerOutput for #ident #ty_generics #where_clause {
+            #[allow(unused_variables)]
+            fn describe(cx: &mut speq::TypeContext, route: &mut speq::RouteSpec) {
+                #(#describe)*
+            }
+        }
+    })
+}
+
+/// Returns statements adding the responses given by any `#[response(...)]` attributes in `attrs`.
+fn responses(attrs: &[Attribute]) -> syn::Result<Vec<proc_macro2::TokenStream>> {
+    attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("response"))
+        .map(|attr| {
+            let args = attr.parse_args::<ResponseArgs>()?;
+            // Handlers respond with 200 unless told otherwise, but these types mostly describe
+            // errors, so they must say which status they respond with.
+            if args.status.is_none() {
+                return Err(syn::Error::new_spanned(
+                    attr,
+                    "responses of `RouteHandlerOutput` types must have a `status`",
+                ));
+            }
+            let response = args.response_spec()?;
+            Ok(quote! {
+                route.responses.push(#response);
+            })
+        })
+        .collect()
+}
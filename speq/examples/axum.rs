@@ -1,9 +1,5 @@
-use std::net::SocketAddr;
-use std::str::FromStr;
-
 use axum::extract::{Query, State};
 use axum::response::IntoResponse;
-use axum::Server;
 use serde::Deserialize;
 use speq::axum::get;
 use speq::Reflect;
@@ -14,9 +10,8 @@ speq::axum_config!(i32);
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!("{:#?}", speq::spec());
 
-    Server::bind(&SocketAddr::from_str("0.0.0.0:8000").unwrap())
-        .serve(speq::axum_router!().with_state(42).into_make_service())
-        .await?;
+    let listener = tokio::net::TcpListener::bind("0.0.0.0:8000").await?;
+    axum::serve(listener, speq::axum_router!().with_state(42)).await?;
 
     Ok(())
 }
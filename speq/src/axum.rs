@@ -1,3 +1,5 @@
+use std::marker::PhantomData;
+
 use axum::handler::Handler;
 use axum::{routing, Router};
 pub use http::Method;
@@ -7,8 +9,11 @@ pub use speq_macros::{
     axum_trace as trace,
 };
 
-use crate::reflection::Reflect;
-use crate::{QuerySpec, RequestSpec, RouteHandlerInput, RouteHandlerInputContext};
+use crate::reflection::{Direction, Format, PrimitiveType, Reflect, Type};
+use crate::{
+    RequestSpec, ResponseSpec, RouteHandlerInput, RouteHandlerInputContext, RouteHandlerOutput,
+    RouteSpec, StatusCode, TypeContext,
+};
 
 #[macro_export]
 macro_rules! axum_config {
@@ -44,6 +49,33 @@ macro_rules! axum_router {
     }};
 }
 
+/// Describes the responses of a handler returning `T`, if `T` implements `RouteHandlerOutput`.
+/// Calling `describe_output` on a `&&OutputDescriber<T>` picks `DescribeOutput` when it applies,
+/// and otherwise falls back to `DescribeOutputFallback`, which does nothing.
+#[doc(hidden)]
+pub struct OutputDescriber<T>(pub PhantomData<fn() -> T>);
+
+#[doc(hidden)]
+pub trait DescribeOutput {
+    fn describe_output(&self, cx: &mut TypeContext, route: &mut RouteSpec);
+}
+
+impl<T: RouteHandlerOutput> DescribeOutput for &OutputDescriber<T> {
+    fn describe_output(&self, cx: &mut TypeContext, route: &mut RouteSpec) {
+        T::describe(cx, route);
+    }
+}
+
+#[doc(hidden)]
+pub trait DescribeOutputFallback {
+    fn describe_output(&self, cx: &mut TypeContext, route: &mut RouteSpec) {
+        let _ = cx;
+        let _ = route;
+    }
+}
+
+impl<T> DescribeOutputFallback for OutputDescriber<T> {}
+
 #[doc(hidden)]
 pub fn register_route<H, T, S>(router: Router<S>, path: &str, method: Method, route: H) -> Router<S>
 where
@@ -68,37 +100,200 @@ where
 }
 
 impl<T: Reflect> RouteHandlerInput for axum::extract::Path<T> {
-    fn describe(cx: &mut RouteHandlerInputContext, route: &mut crate::RouteSpec) {
-        route.path.params = Some(T::reflect(cx.type_cx));
+    fn describe(cx: &mut RouteHandlerInputContext, route: &mut RouteSpec) {
+        route.path.params = Some(cx.type_cx.reflect_as::<T>(Direction::Input));
     }
 }
 
+#[cfg(feature = "serde_qs-axum")]
 impl<T: Reflect> RouteHandlerInput for serde_qs::axum::QsQuery<T> {
-    fn describe(cx: &mut RouteHandlerInputContext, route: &mut crate::RouteSpec) {
-        route.query = Some(QuerySpec {
-            type_desc: T::reflect(cx.type_cx),
+    fn describe(cx: &mut RouteHandlerInputContext, route: &mut RouteSpec) {
+        describe_query::<T>(cx, route);
+    }
+}
+
+impl<T: Reflect> RouteHandlerInput for axum::extract::Json<T> {
+    fn describe(cx: &mut RouteHandlerInputContext, route: &mut RouteSpec) {
+        route.request = Some(RequestSpec {
+            type_desc: cx.type_cx.reflect_as::<T>(Direction::Input),
             is_optional: cx.is_optional,
+            media_type: "application/json".into(),
         });
     }
 }
 
-impl<T: Reflect> RouteHandlerInput for axum::extract::Json<T> {
-    fn describe(cx: &mut RouteHandlerInputContext, route: &mut crate::RouteSpec) {
+impl RouteHandlerInput for String {
+    fn describe(cx: &mut RouteHandlerInputContext, route: &mut RouteSpec) {
+        route.request = Some(RequestSpec {
+            type_desc: cx.type_cx.reflect_as::<String>(Direction::Input),
+            is_optional: cx.is_optional,
+            media_type: "text/plain".into(),
+        });
+    }
+}
+
+impl RouteHandlerInput for axum::body::Bytes {
+    fn describe(cx: &mut RouteHandlerInputContext, route: &mut RouteSpec) {
+        route.request = Some(RequestSpec {
+            type_desc: binary(),
+            is_optional: cx.is_optional,
+            media_type: "application/octet-stream".into(),
+        });
+    }
+}
+
+/// The type of a body of arbitrary bytes.
+fn binary() -> Type {
+    Type::Formatted(PrimitiveType::String, Format::Binary)
+}
+
+#[cfg(feature = "axum-query")]
+impl<T: Reflect> RouteHandlerInput for axum::extract::Query<T> {
+    fn describe(cx: &mut RouteHandlerInputContext, route: &mut RouteSpec) {
+        describe_query::<T>(cx, route);
+    }
+}
+
+impl RouteHandlerInput for axum::extract::RawQuery {}
+
+#[cfg(feature = "axum-form")]
+impl<T: Reflect> RouteHandlerInput for axum::Form<T> {
+    fn describe(cx: &mut RouteHandlerInputContext, route: &mut RouteSpec) {
+        describe_form::<T>(cx, route);
+    }
+}
+
+#[cfg(feature = "axum-extra-query")]
+impl<T: Reflect> RouteHandlerInput for axum_extra::extract::Query<T> {
+    fn describe(cx: &mut RouteHandlerInputContext, route: &mut RouteSpec) {
+        describe_query::<T>(cx, route);
+    }
+}
+
+#[cfg(feature = "axum-extra-query")]
+impl<T: Reflect> RouteHandlerInput for axum_extra::extract::OptionalQuery<T> {
+    fn describe(cx: &mut RouteHandlerInputContext, route: &mut RouteSpec) {
+        cx.is_optional = true;
+        describe_query::<T>(cx, route);
+    }
+}
+
+#[cfg(feature = "axum-extra-form")]
+impl<T: Reflect> RouteHandlerInput for axum_extra::extract::Form<T> {
+    fn describe(cx: &mut RouteHandlerInputContext, route: &mut RouteSpec) {
+        describe_form::<T>(cx, route);
+    }
+}
+
+#[cfg(any(
+    feature = "axum-query",
+    feature = "axum-form",
+    feature = "axum-extra-query",
+    feature = "axum-extra-form",
+    feature = "serde_qs-axum",
+))]
+fn describe_query<T: Reflect>(cx: &mut RouteHandlerInputContext, route: &mut RouteSpec) {
+    route.query = Some(crate::QuerySpec {
+        type_desc: cx.type_cx.reflect_as::<T>(Direction::Input),
+        is_optional: cx.is_optional,
+    });
+}
+
+/// Forms are read from the query string of `GET` and `HEAD` requests, and from the body of
+/// others.
+#[cfg(any(feature = "axum-form", feature = "axum-extra-form"))]
+fn describe_form<T: Reflect>(cx: &mut RouteHandlerInputContext, route: &mut RouteSpec) {
+    if route.method == Method::GET || route.method == Method::HEAD {
+        describe_query::<T>(cx, route);
+    } else {
         route.request = Some(RequestSpec {
-            type_desc: T::reflect(cx.type_cx),
+            type_desc: cx.type_cx.reflect_as::<T>(Direction::Input),
             is_optional: cx.is_optional,
+            media_type: "application/x-www-form-urlencoded".into(),
         });
     }
 }
 
+#[cfg(feature = "axum-multipart")]
 impl RouteHandlerInput for axum::extract::Multipart {
     // TODO: Describe multipart request
 }
 
 impl<T> RouteHandlerInput for axum::extract::Extension<T> {}
 
+impl<S> RouteHandlerInput for axum::extract::State<S> {}
+
+#[cfg(feature = "axum-extra-typed-header")]
+impl<H: axum_extra::headers::Header> RouteHandlerInput for axum_extra::TypedHeader<H> {
+    fn describe(cx: &mut RouteHandlerInputContext, route: &mut RouteSpec) {
+        route.headers.push(crate::HeaderSpec {
+            name: H::name().as_str().into(),
+            type_desc: None,
+            is_optional: cx.is_optional,
+        });
+    }
+}
+
 #[cfg(feature = "axum-extra-cookie")]
 impl RouteHandlerInput for axum_extra::extract::cookie::CookieJar {}
 
 #[cfg(feature = "axum-extra-cookie-private")]
 impl RouteHandlerInput for axum_extra::extract::cookie::PrivateCookieJar {}
+
+impl<T: Reflect> RouteHandlerOutput for axum::Json<T> {
+    fn describe(cx: &mut TypeContext, route: &mut RouteSpec) {
+        route.responses.push(ResponseSpec {
+            status: Some(StatusCode::OK),
+            description: None,
+            type_desc: Some(cx.reflect_as::<T>(Direction::Output)),
+            media_type: Some("application/json".into()),
+            example: None,
+        });
+    }
+}
+
+impl<T> RouteHandlerOutput for axum::response::Html<T> {
+    fn describe(cx: &mut TypeContext, route: &mut RouteSpec) {
+        route.responses.push(ResponseSpec {
+            status: Some(StatusCode::OK),
+            description: None,
+            type_desc: Some(cx.reflect_as::<String>(Direction::Output)),
+            media_type: Some("text/html".into()),
+            example: None,
+        });
+    }
+}
+
+impl RouteHandlerOutput for axum::body::Bytes {
+    fn describe(_: &mut TypeContext, route: &mut RouteSpec) {
+        route.responses.push(ResponseSpec {
+            status: Some(StatusCode::OK),
+            description: None,
+            type_desc: Some(binary()),
+            media_type: Some("application/octet-stream".into()),
+            example: None,
+        });
+    }
+}
+
+impl RouteHandlerOutput for Vec<u8> {
+    fn describe(cx: &mut TypeContext, route: &mut RouteSpec) {
+        <axum::body::Bytes as RouteHandlerOutput>::describe(cx, route);
+    }
+}
+
+impl RouteHandlerOutput for axum::response::NoContent {
+    fn describe(_: &mut TypeContext, route: &mut RouteSpec) {
+        route.responses.push(ResponseSpec {
+            status: Some(StatusCode::NO_CONTENT),
+            description: None,
+            type_desc: None,
+            media_type: None,
+            example: None,
+        });
+    }
+}
+
+impl RouteHandlerOutput for axum::response::Redirect {}
+
+impl RouteHandlerOutput for axum::response::Response {}
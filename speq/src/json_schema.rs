@@ -0,0 +1,832 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+
+use serde_json::{json, Map, Value};
+
+use crate::reflection::{
+    Constraints, EnumTag, EnumType, EnumVariant, EnumVariantKind, Field, FloatWidth, PrimitiveType,
+    Reflect, Type, TypeContext, TypeDecl,
+};
+use crate::SpeqStr;
+
+/// Generates a JSON Schema (draft 2020-12) for `T`, with every type declaration it refers to
+/// placed under `$defs`.
+pub fn for_type<T: Reflect + ?Sized>(naming: NamingStrategy) -> Result<Value, NameConflict> {
+    let mut cx = TypeContext::new();
+    let ty = T::reflect(&mut cx);
+    let types = cx.into_types();
+    let names = naming.names(&types)?;
+
+    let generator = Generator::new(&types, &names, Dialect::Draft2020_12, "#/$defs/");
+
+    let mut defs = BTreeMap::new();
+    for (id, decl) in &types {
+        generator.decl_schemas(&names[id], decl, &mut defs);
+    }
+
+    let mut schema = Map::new();
+    schema.insert(
+        "$schema".to_owned(),
+        json!("https://json-schema.org/draft/2020-12/schema"),
+    );
+
+    if let Value::Object(root) = generator.schema(&ty) {
+        schema.extend(root);
+    }
+
+    if !defs.is_empty() {
+        schema.insert("$defs".to_owned(), json!(defs));
+    }
+
+    Ok(Value::Object(schema))
+}
+
+/// How the schemas of type declarations are named.
+#[derive(Clone, Copy, Debug, Default)]
+pub enum NamingStrategy {
+    /// Names schemas after their types, e.g. `User` or `Page_User`. A type's name can be set with
+    /// `#[speq(name = "...")]`.
+    #[default]
+    Short,
+    /// Names schemas after the full paths of their types, e.g. `my_crate.models.User`.
+    FullyQualified,
+    /// Names schemas with a function of each type's id and declaration.
+    Custom(fn(&str, &TypeDecl) -> String),
+}
+
+impl NamingStrategy {
+    /// Names every declaration in `types`, failing if two of them end up with the same name.
+    pub(crate) fn names(
+        &self,
+        types: &HashMap<SpeqStr, TypeDecl>,
+    ) -> Result<HashMap<SpeqStr, String>, NameConflict> {
+        let mut ids = types.keys().collect::<Vec<_>>();
+        ids.sort();
+
+        let mut names = HashMap::new();
+        let mut named = HashMap::<String, &SpeqStr>::new();
+        for id in ids {
+            let decl = &types[id];
+            let name = match self {
+                NamingStrategy::Short => schema_name(decl.name()),
+                NamingStrategy::FullyQualified => schema_name(id),
+                NamingStrategy::Custom(f) => f(id, decl),
+            };
+
+            if let Some(other) = named.insert(name.clone(), id) {
+                return Err(NameConflict {
+                    name,
+                    ids: [other.clone(), id.clone()],
+                });
+            }
+
+            names.insert(id.clone(), name);
+        }
+
+        Ok(names)
+    }
+}
+
+/// Two types were given the same schema name.
+#[derive(Clone, Debug)]
+pub struct NameConflict {
+    pub name: String,
+    pub ids: [SpeqStr; 2],
+}
+
+impl fmt::Display for NameConflict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "types `{}` and `{}` are both named `{}`",
+            self.ids[0], self.ids[1], self.name
+        )
+    }
+}
+
+impl std::error::Error for NameConflict {}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Dialect {
+    /// The JSON Schema subset understood by OpenAPI 3.0.
+    OpenApi3_0,
+    /// JSON Schema draft 2020-12, which OpenAPI 3.1 also uses.
+    Draft2020_12,
+}
+
+fn schema_name(id: &str) -> String {
+    let mut name = String::with_capacity(id.len());
+    // Lifetimes are dropped, and runs of punctuation, such as the `<>` in generic ids, become a
+    // single `_` (or nothing at the end of the name).
+    let mut separated = false;
+    let mut lifetime = false;
+    for c in id.replace("::", ".").chars() {
+        match c {
+            'a'..='z' | 'A'..='Z' | '0'..='9' | '_' if lifetime => {}
+            'a'..='z' | 'A'..='Z' | '0'..='9' | '.' | '-' | '_' => {
+                if separated {
+                    name.push('_');
+                    separated = false;
+                }
+                name.push(c);
+            }
+            _ => {
+                separated = true;
+                lifetime = c == '\'';
+            }
+        }
+    }
+    name
+}
+
+fn is_required(field: &Field) -> bool {
+    field.required && !matches!(field.type_desc, Type::Option(_))
+}
+
+pub(crate) struct Generator<'a> {
+    types: &'a HashMap<SpeqStr, TypeDecl>,
+    names: &'a HashMap<SpeqStr, String>,
+    dialect: Dialect,
+    ref_prefix: &'static str,
+}
+
+/// The pieces of an object schema assembled from a list of (possibly flattened) fields.
+#[derive(Default)]
+struct ObjectSchema {
+    properties: Map<String, Value>,
+    required: Vec<String>,
+    additional: Option<Value>,
+    all_of: Vec<Value>,
+}
+
+impl ObjectSchema {
+    fn into_schema(self) -> Value {
+        let mut schema = json!({
+            "type": "object",
+            "properties": self.properties,
+        });
+
+        if !self.required.is_empty() {
+            schema["required"] = json!(self.required);
+        }
+
+        if let Some(additional) = self.additional {
+            schema["additionalProperties"] = additional;
+        }
+
+        if self.all_of.is_empty() {
+            schema
+        } else {
+            let mut all_of = vec![schema];
+            all_of.extend(self.all_of);
+            json!({ "allOf": all_of })
+        }
+    }
+}
+
+impl<'a> Generator<'a> {
+    pub(crate) fn new(
+        types: &'a HashMap<SpeqStr, TypeDecl>,
+        names: &'a HashMap<SpeqStr, String>,
+        dialect: Dialect,
+        ref_prefix: &'static str,
+    ) -> Generator<'a> {
+        Generator {
+            types,
+            names,
+            dialect,
+            ref_prefix,
+        }
+    }
+
+    /// Follows references to aliases until reaching the type they stand for.
+    pub(crate) fn resolve<'b>(&'b self, mut ty: &'b Type) -> &'b Type {
+        while let Some(TypeDecl::Alias(alias)) = ty.as_id().and_then(|id| self.types.get(id)) {
+            ty = &alias.type_desc;
+        }
+        ty
+    }
+
+    fn decl(&self, ty: &Type) -> Option<&TypeDecl> {
+        self.resolve(ty).as_id().and_then(|id| self.types.get(id))
+    }
+
+    /// Returns the fields of a struct type along with whether each is required, with flattened
+    /// struct fields expanded in place.
+    pub(crate) fn struct_fields<'b>(&'b self, ty: &'b Type) -> Option<Vec<(&'b Field, bool)>> {
+        let decl = self.decl(ty)?.as_struct()?;
+        let mut fields = vec![];
+        self.collect_fields(
+            &decl.fields,
+            true,
+            &mut self.resolve(ty).as_id().into_iter().collect(),
+            &mut fields,
+        );
+        Some(fields)
+    }
+
+    fn collect_fields<'b>(
+        &'b self,
+        fields: &'b [Field],
+        required: bool,
+        flattened: &mut Vec<&'b str>,
+        out: &mut Vec<(&'b Field, bool)>,
+    ) {
+        for field in fields {
+            if field.flatten {
+                let (ty, optional) = match self.resolve(&field.type_desc) {
+                    Type::Option(ty) => (self.resolve(ty), true),
+                    ty => (ty, false),
+                };
+
+                if let Some(decl) = self.decl(ty).and_then(TypeDecl::as_struct) {
+                    let id = ty.as_id().unwrap();
+                    if flattened.contains(&id) {
+                        continue;
+                    }
+
+                    flattened.push(id);
+                    self.collect_fields(&decl.fields, required && !optional, flattened, out);
+                    flattened.pop();
+                    continue;
+                }
+            }
+
+            out.push((field, required && is_required(field)));
+        }
+    }
+
+    pub(crate) fn is_object(&self, ty: &Type) -> bool {
+        match self.resolve(ty) {
+            Type::Option(ty) => self.is_object(ty),
+            Type::Map { .. } => true,
+            Type::Id(_) => matches!(self.decl(ty), Some(TypeDecl::Struct(_))),
+            _ => false,
+        }
+    }
+
+    pub(crate) fn schema(&self, ty: &Type) -> Value {
+        match ty {
+            Type::Primitive(primitive) => primitive_schema(primitive),
+            Type::Formatted(primitive, format) => {
+                let mut schema = primitive_schema(primitive);
+                schema["format"] = json!(format.as_str());
+                schema
+            }
+            Type::Any => json!({}),
+            Type::Option(ty) => self.nullable(self.schema(ty)),
+            Type::Array(ty) => json!({
+                "type": "array",
+                "items": self.schema(ty),
+            }),
+            Type::Set(ty) => json!({
+                "type": "array",
+                "items": self.schema(ty),
+                "uniqueItems": true,
+            }),
+            Type::FixedArray(ty, len) => json!({
+                "type": "array",
+                "items": self.schema(ty),
+                "minItems": len,
+                "maxItems": len,
+            }),
+            Type::Tuple(types) if types.is_empty() => self.null_schema(),
+            Type::Tuple(types) => self.tuple_schema(types),
+            Type::Map { key, value } => {
+                let mut schema = json!({
+                    "type": "object",
+                    "additionalProperties": self.schema(value),
+                });
+
+                if let Some(key) = self.key_schema(key) {
+                    schema["propertyNames"] = key;
+                }
+
+                schema
+            }
+            Type::Id(id) => {
+                let name = match self.names.get(id) {
+                    Some(name) => name.clone(),
+                    None => schema_name(id),
+                };
+                json!({ "$ref": format!("{}{name}", self.ref_prefix) })
+            }
+        }
+    }
+
+    /// Describes the property names of a map keyed by `key`. serde writes keys as strings, so
+    /// only keys which are not already strings need a schema.
+    fn key_schema(&self, key: &Type) -> Option<Value> {
+        if self.dialect == Dialect::OpenApi3_0 {
+            return None;
+        }
+
+        let (primitive, format) = match self.resolve(key) {
+            Type::Primitive(primitive) => (primitive, None),
+            Type::Formatted(primitive, format) => (primitive, Some(format)),
+            key @ Type::Id(_) if matches!(self.decl(key), Some(TypeDecl::Enum(_))) => {
+                return Some(self.schema(key));
+            }
+            _ => return None,
+        };
+
+        match (primitive, format) {
+            (PrimitiveType::String, Some(format)) => Some(json!({ "format": format.as_str() })),
+            (PrimitiveType::Int(_), _) => Some(json!({ "pattern": "^-?[0-9]+$" })),
+            (PrimitiveType::UInt(_), _) => Some(json!({ "pattern": "^[0-9]+$" })),
+            (PrimitiveType::Bool, _) => Some(json!({ "enum": ["true", "false"] })),
+            (PrimitiveType::Char, _) => Some(json!({ "minLength": 1, "maxLength": 1 })),
+            _ => None,
+        }
+    }
+
+    fn tuple_schema(&self, types: &[Type]) -> Value {
+        let items = types.iter().map(|ty| self.schema(ty)).collect::<Vec<_>>();
+        match self.dialect {
+            Dialect::OpenApi3_0 => {
+                let mut distinct = Vec::<Value>::new();
+                for item in items {
+                    if !distinct.contains(&item) {
+                        distinct.push(item);
+                    }
+                }
+
+                let items = if distinct.len() == 1 {
+                    distinct.remove(0)
+                } else {
+                    json!({ "anyOf": distinct })
+                };
+
+                json!({
+                    "type": "array",
+                    "items": items,
+                    "minItems": types.len(),
+                    "maxItems": types.len(),
+                })
+            }
+            Dialect::Draft2020_12 => json!({
+                "type": "array",
+                "prefixItems": items,
+                "items": false,
+                "minItems": types.len(),
+                "maxItems": types.len(),
+            }),
+        }
+    }
+
+    /// Returns the schema of a variant's content, or `None` for unit variants.
+    fn variant_schema(&self, kind: &EnumVariantKind) -> Option<Value> {
+        match kind {
+            EnumVariantKind::Unit => None,
+            EnumVariantKind::NewType(ty) => Some(self.schema(ty)),
+            EnumVariantKind::Tuple(types) => Some(self.tuple_schema(types)),
+            EnumVariantKind::Struct(fields) => Some(self.object_schema(fields).into_schema()),
+        }
+    }
+
+    fn nullable(&self, mut schema: Value) -> Value {
+        match self.dialect {
+            Dialect::OpenApi3_0 => {
+                if schema.get("$ref").is_some() {
+                    return json!({ "allOf": [schema], "nullable": true });
+                }
+
+                if let Some(Value::Array(values)) = schema.get_mut("enum") {
+                    values.push(Value::Null);
+                }
+
+                schema["nullable"] = json!(true);
+                schema
+            }
+            Dialect::Draft2020_12 => match schema.get("type") {
+                Some(Value::String(name)) if schema.get("enum").is_none() => {
+                    schema["type"] = json!([name, "null"]);
+                    schema
+                }
+                _ => json!({ "anyOf": [schema, { "type": "null" }] }),
+            },
+        }
+    }
+
+    /// Adds `annotations`, such as a description, to `schema`. OpenAPI 3.0 ignores the siblings
+    /// of a `$ref`, so references are wrapped in an `allOf` there.
+    fn annotate(&self, mut schema: Value, annotations: Map<String, Value>) -> Value {
+        if annotations.is_empty() {
+            return schema;
+        }
+
+        if self.dialect == Dialect::OpenApi3_0 && schema.get("$ref").is_some() {
+            schema = json!({ "allOf": [schema] });
+        }
+
+        if let Value::Object(schema) = &mut schema {
+            schema.extend(annotations);
+        }
+
+        schema
+    }
+
+    fn describe(&self, schema: Value, doc: Option<&SpeqStr>) -> Value {
+        let mut annotations = Map::new();
+        if let Some(doc) = doc {
+            annotations.insert("description".to_owned(), json!(doc));
+        }
+        self.annotate(schema, annotations)
+    }
+
+    /// Adds an example value to `annotations`. OpenAPI 3.0 only has the singular `example`.
+    fn add_example(&self, annotations: &mut Map<String, Value>, example: Option<&Value>) {
+        let Some(example) = example else {
+            return;
+        };
+
+        match self.dialect {
+            Dialect::OpenApi3_0 => annotations.insert("example".to_owned(), example.clone()),
+            Dialect::Draft2020_12 => annotations.insert("examples".to_owned(), json!([example])),
+        };
+    }
+
+    /// Returns the schema of a field's type, restricted by the field's constraints.
+    pub(crate) fn constrained_schema(&self, field: &Field) -> Value {
+        let Constraints { min, max, pattern } = &field.constraints;
+        let mut keywords = Map::new();
+
+        let mut ty = self.resolve(&field.type_desc);
+        while let Type::Option(inner) = ty {
+            ty = self.resolve(inner);
+        }
+
+        // The meaning of `min` and `max` depends on what they are applied to.
+        let bounds = match ty {
+            Type::Primitive(primitive) | Type::Formatted(primitive, _) => match primitive {
+                PrimitiveType::Int(_) | PrimitiveType::UInt(_) | PrimitiveType::Float(_) => {
+                    Some(("minimum", "maximum"))
+                }
+                PrimitiveType::Char | PrimitiveType::String => Some(("minLength", "maxLength")),
+                PrimitiveType::Bool => None,
+            },
+            Type::Array(_) | Type::Set(_) | Type::FixedArray(..) | Type::Tuple(_) => {
+                Some(("minItems", "maxItems"))
+            }
+            Type::Map { .. } => Some(("minProperties", "maxProperties")),
+            Type::Id(_) if self.is_object(ty) => Some(("minProperties", "maxProperties")),
+            _ => None,
+        };
+
+        if let Some((min_keyword, max_keyword)) = bounds {
+            if let Some(min) = min {
+                keywords.insert(min_keyword.to_owned(), number(*min));
+            }
+            if let Some(max) = max {
+                keywords.insert(max_keyword.to_owned(), number(*max));
+            }
+        }
+
+        if let Some(pattern) = pattern {
+            keywords.insert("pattern".to_owned(), json!(pattern));
+        }
+
+        self.annotate(self.schema(&field.type_desc), keywords)
+    }
+
+    fn field_schema(&self, field: &Field, write_only: bool) -> Value {
+        let mut annotations = Map::new();
+        if let Some(doc) = &field.doc {
+            annotations.insert("description".to_owned(), json!(doc));
+        }
+        if field.read_only {
+            annotations.insert("readOnly".to_owned(), json!(true));
+        }
+        if write_only {
+            annotations.insert("writeOnly".to_owned(), json!(true));
+        }
+        self.add_example(&mut annotations, field.example.as_ref());
+        self.annotate(self.constrained_schema(field), annotations)
+    }
+
+    fn null_schema(&self) -> Value {
+        match self.dialect {
+            Dialect::OpenApi3_0 => json!({ "enum": [null], "nullable": true }),
+            Dialect::Draft2020_12 => json!({ "type": "null" }),
+        }
+    }
+
+    /// Returns the schema of the tag identifying `variant` among `variants`.
+    fn tag_schema(&self, variant: &EnumVariant, variants: &[EnumVariant]) -> Value {
+        if variant.other {
+            let known = variants
+                .iter()
+                .filter(|other| !other.other)
+                .flat_map(|other| std::iter::once(&other.tag_value).chain(&other.aliases))
+                .collect::<Vec<_>>();
+
+            return json!({
+                "type": "string",
+                "not": { "enum": known },
+            });
+        }
+
+        if variant.aliases.is_empty() {
+            return self.const_schema(&variant.tag_value);
+        }
+
+        let values = std::iter::once(&variant.tag_value)
+            .chain(&variant.aliases)
+            .collect::<Vec<_>>();
+
+        json!({
+            "type": "string",
+            "enum": values,
+        })
+    }
+
+    fn const_schema(&self, value: &str) -> Value {
+        match self.dialect {
+            Dialect::OpenApi3_0 => json!({
+                "type": "string",
+                "enum": [value],
+            }),
+            Dialect::Draft2020_12 => json!({
+                "type": "string",
+                "const": value,
+            }),
+        }
+    }
+
+    /// Inserts the schema for a type declaration into `schemas` under `name`. This may also add
+    /// schemas for individual enum variants, which OpenAPI 3.0 discriminators need to refer to.
+    pub(crate) fn decl_schemas(
+        &self,
+        name: &str,
+        decl: &TypeDecl,
+        schemas: &mut BTreeMap<String, Value>,
+    ) {
+        let (schema, doc, example) = match decl {
+            TypeDecl::Struct(decl) => (
+                self.object_schema(&decl.fields).into_schema(),
+                &decl.doc,
+                &decl.example,
+            ),
+            TypeDecl::Enum(decl) => (
+                self.enum_schema(name, decl, schemas),
+                &decl.doc,
+                &decl.example,
+            ),
+            TypeDecl::Alias(decl) => (self.schema(&decl.type_desc), &decl.doc, &decl.example),
+        };
+
+        let mut annotations = Map::new();
+        if let Some(doc) = doc {
+            annotations.insert("description".to_owned(), json!(doc));
+        }
+        self.add_example(&mut annotations, example.as_ref());
+
+        schemas.insert(name.to_owned(), self.annotate(schema, annotations));
+    }
+
+    fn object_schema(&self, fields: &[Field]) -> ObjectSchema {
+        let mut object = ObjectSchema::default();
+        self.add_fields(fields, true, &mut vec![], &mut object);
+        object
+    }
+
+    /// Adds `fields` to `object`. `flattened` holds the types currently being flattened, so that a
+    /// type which (optionally) flattens itself only contributes its fields once.
+    fn add_fields<'b>(
+        &'b self,
+        fields: &'b [Field],
+        required: bool,
+        flattened: &mut Vec<&'b str>,
+        object: &mut ObjectSchema,
+    ) {
+        for field in fields {
+            if !field.flatten {
+                object.properties.insert(
+                    field.name.to_string(),
+                    self.field_schema(field, field.write_only),
+                );
+
+                // Aliases are only read, so a required field may be given under any of them.
+                for alias in &field.aliases {
+                    object
+                        .properties
+                        .insert(alias.to_string(), self.field_schema(field, true));
+                }
+
+                if required && is_required(field) {
+                    if field.aliases.is_empty() {
+                        object.required.push(field.name.to_string());
+                    } else {
+                        let any_of = std::iter::once(&field.name)
+                            .chain(&field.aliases)
+                            .map(|name| json!({ "required": [name] }))
+                            .collect::<Vec<_>>();
+                        object.all_of.push(json!({ "anyOf": any_of }));
+                    }
+                }
+                continue;
+            }
+
+            let (ty, optional) = match self.resolve(&field.type_desc) {
+                Type::Option(ty) => (self.resolve(ty), true),
+                ty => (ty, false),
+            };
+
+            match (ty, self.decl(ty)) {
+                (Type::Id(id), Some(TypeDecl::Struct(decl))) => {
+                    if flattened.contains(&id.as_ref()) {
+                        continue;
+                    }
+
+                    flattened.push(id);
+                    self.add_fields(&decl.fields, required && !optional, flattened, object);
+                    flattened.pop();
+                }
+                (Type::Map { value, .. }, _) => {
+                    object.additional = Some(self.schema(value));
+                }
+                _ => object.all_of.push(self.schema(ty)),
+            }
+        }
+    }
+
+    fn enum_schema(
+        &self,
+        name: &str,
+        decl: &EnumType,
+        schemas: &mut BTreeMap<String, Value>,
+    ) -> Value {
+        let Some(tag) = &decl.tag else {
+            let variants = decl
+                .variants
+                .iter()
+                .map(|variant| {
+                    let schema = self
+                        .variant_schema(&variant.kind)
+                        .unwrap_or_else(|| self.null_schema());
+                    self.describe(schema, variant.doc.as_ref())
+                })
+                .collect::<Vec<_>>();
+
+            return json!({ "anyOf": variants });
+        };
+
+        // OpenAPI 3.0 has no `const`, so tagged variants are instead told apart through a
+        // discriminator, which can only map tag values onto references.
+        let discriminator = match tag {
+            EnumTag::Internal(tag) | EnumTag::Adjacent { tag, .. }
+                if self.dialect == Dialect::OpenApi3_0 =>
+            {
+                Some(tag)
+            }
+            _ => None,
+        };
+
+        let mut unit_variants = Vec::<&SpeqStr>::new();
+        let mut variants = vec![];
+        let mut mapping = Map::new();
+
+        for variant in &decl.variants {
+            let tag_values = std::iter::once(&variant.tag_value)
+                .chain(&variant.aliases)
+                .collect::<Vec<_>>();
+            let schema = match (tag, &variant.kind) {
+                (EnumTag::External, kind) => {
+                    let Some(content) = self.variant_schema(kind) else {
+                        unit_variants.extend(&tag_values);
+                        continue;
+                    };
+
+                    let mut objects = tag_values
+                        .iter()
+                        .map(|tag_value| {
+                            json!({
+                                "type": "object",
+                                "properties": { tag_value.to_string(): content },
+                                "required": [tag_value],
+                                "additionalProperties": false,
+                            })
+                        })
+                        .collect::<Vec<_>>();
+
+                    if objects.len() == 1 {
+                        objects.remove(0)
+                    } else {
+                        json!({ "anyOf": objects })
+                    }
+                }
+                (EnumTag::Internal(tag), kind) => {
+                    // serde rejects internally tagged tuple variants, so they can only show up
+                    // here through a hand-written declaration.
+                    let mut object = match kind {
+                        EnumVariantKind::Struct(fields) => self.object_schema(fields),
+                        kind => ObjectSchema {
+                            all_of: self.variant_schema(kind).into_iter().collect(),
+                            ..Default::default()
+                        },
+                    };
+
+                    object
+                        .properties
+                        .insert(tag.to_string(), self.tag_schema(variant, &decl.variants));
+                    object.required.insert(0, tag.to_string());
+                    object.into_schema()
+                }
+                (EnumTag::Adjacent { tag, content }, kind) => {
+                    let mut object = ObjectSchema::default();
+                    object
+                        .properties
+                        .insert(tag.to_string(), self.tag_schema(variant, &decl.variants));
+                    object.required.push(tag.to_string());
+
+                    if let Some(content_schema) = self.variant_schema(kind) {
+                        object
+                            .properties
+                            .insert(content.to_string(), content_schema);
+                        object.required.push(content.to_string());
+                    }
+
+                    object.into_schema()
+                }
+            };
+
+            let schema = self.describe(schema, variant.doc.as_ref());
+
+            if discriminator.is_some() {
+                let variant_name = format!("{name}.{}", variant.name);
+                let reference = format!("{}{variant_name}", self.ref_prefix);
+                if !variant.other {
+                    for tag_value in tag_values {
+                        mapping.insert(tag_value.to_string(), json!(reference));
+                    }
+                }
+                schemas.insert(variant_name, schema);
+                variants.push(json!({ "$ref": reference }));
+            } else {
+                variants.push(schema);
+            }
+        }
+
+        if !unit_variants.is_empty() {
+            let schema = json!({
+                "type": "string",
+                "enum": unit_variants,
+            });
+
+            if variants.is_empty() {
+                return schema;
+            }
+
+            variants.insert(0, schema);
+        }
+
+        let mut schema = json!({ "oneOf": variants });
+
+        if let Some(tag) = discriminator {
+            schema["discriminator"] = json!({
+                "propertyName": tag,
+                "mapping": mapping,
+            });
+        }
+
+        schema
+    }
+}
+
+/// Writes whole numbers as integers, since some keywords only accept those.
+fn number(value: f64) -> Value {
+    if value.fract() == 0.0 && value.abs() < (1u64 << 53) as f64 {
+        json!(value as i64)
+    } else {
+        json!(value)
+    }
+}
+
+fn primitive_schema(primitive: &PrimitiveType) -> Value {
+    match primitive {
+        PrimitiveType::Bool => json!({ "type": "boolean" }),
+        PrimitiveType::Int(width) => json!({
+            "type": "integer",
+            "format": format!("int{}", width.as_u8()),
+        }),
+        PrimitiveType::UInt(width) => json!({
+            "type": "integer",
+            "format": format!("uint{}", width.as_u8()),
+            "minimum": 0,
+        }),
+        PrimitiveType::Float(FloatWidth::F32) => json!({
+            "type": "number",
+            "format": "float",
+        }),
+        PrimitiveType::Float(FloatWidth::F64) => json!({
+            "type": "number",
+            "format": "double",
+        }),
+        PrimitiveType::Char => json!({
+            "type": "string",
+            "minLength": 1,
+            "maxLength": 1,
+        }),
+        PrimitiveType::String => json!({ "type": "string" }),
+    }
+}
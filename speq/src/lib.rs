@@ -1,13 +1,19 @@
 #[cfg(feature = "axum")]
 pub mod axum;
+pub mod json_schema;
+pub mod openapi;
 pub mod reflection;
 
 use std::borrow::Cow;
 use std::collections::HashMap;
 
+use crate::reflection::Direction;
+
 pub use http::{Method, StatusCode};
 pub use inventory;
-pub use speq_macros::Reflect;
+#[doc(hidden)]
+pub use serde_json;
+pub use speq_macros::{Reflect, RouteHandlerOutput};
 
 pub use reflection::{Type, TypeContext, TypeDecl};
 
@@ -36,13 +42,19 @@ pub struct QuerySpec {
 pub struct RequestSpec {
     pub type_desc: Type,
     pub is_optional: bool,
+    /// The media type of the body, such as `application/json`.
+    pub media_type: SpeqStr,
 }
 
 #[derive(Clone, Debug)]
 pub struct ResponseSpec {
-    pub status: StatusCode,
+    /// The status of the response, or `None` if it is only known at runtime.
+    pub status: Option<StatusCode>,
     pub description: Option<SpeqStr>,
     pub type_desc: Option<Type>,
+    /// The media type of the body, or `None` if the response has none.
+    pub media_type: Option<SpeqStr>,
+    pub example: Option<serde_json::Value>,
 }
 
 #[derive(Clone, Debug)]
@@ -58,6 +70,67 @@ pub struct RouteSpec {
     pub responses: Vec<ResponseSpec>,
 }
 
+impl RouteSpec {
+    /// Adds responses given explicitly, such as by `#[response]` attributes. Each replaces any
+    /// response already described, such as one inferred from a handler's return type, with the
+    /// same media type and either the same status or one only known at runtime (since explicit
+    /// responses say what that status is). Explicit responses without a media type or model only
+    /// describe the status, so they replace those of every media type and keep their content.
+    pub fn override_responses(&mut self, responses: impl IntoIterator<Item = ResponseSpec>) {
+        let described = std::mem::take(&mut self.responses);
+        let mut replaced = vec![false; described.len()];
+        let mut explicit = vec![];
+        for response in responses {
+            let has_content = response.media_type.is_some() || response.type_desc.is_some();
+            let mut kept_content = false;
+            for (existing, replaced) in described.iter().zip(&mut replaced) {
+                if existing.status.is_some() && existing.status != response.status {
+                    continue;
+                }
+
+                if !has_content {
+                    explicit.push(ResponseSpec {
+                        status: response.status,
+                        description: response.description.clone(),
+                        example: response.example.clone().or(existing.example.clone()),
+                        ..existing.clone()
+                    });
+                    kept_content = true;
+                    *replaced = true;
+                } else if response.media_type == existing.media_type {
+                    *replaced = true;
+                }
+            }
+
+            if !kept_content {
+                explicit.push(response);
+            }
+        }
+
+        self.responses = described
+            .into_iter()
+            .zip(replaced)
+            .filter(|(_, replaced)| !replaced)
+            .map(|(response, _)| response)
+            .chain(explicit)
+            .collect();
+    }
+
+    fn types_mut(&mut self) -> impl Iterator<Item = &mut Type> {
+        self.path
+            .params
+            .iter_mut()
+            .chain(self.headers.iter_mut().filter_map(|h| h.type_desc.as_mut()))
+            .chain(self.query.iter_mut().map(|q| &mut q.type_desc))
+            .chain(self.request.iter_mut().map(|r| &mut r.type_desc))
+            .chain(
+                self.responses
+                    .iter_mut()
+                    .filter_map(|r| r.type_desc.as_mut()),
+            )
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct ApiSpec {
     pub routes: Vec<RouteSpec>,
@@ -81,7 +154,7 @@ pub struct RouteHandlerInputContext<'a> {
 }
 
 impl RouteHandlerInputContext<'_> {
-    pub fn new(type_cx: &mut TypeContext) -> RouteHandlerInputContext {
+    pub fn new(type_cx: &mut TypeContext) -> RouteHandlerInputContext<'_> {
         RouteHandlerInputContext {
             type_cx,
             is_optional: false,
@@ -110,6 +183,82 @@ impl<T: RouteHandlerInput, E> RouteHandlerInput for Result<T, E> {
     }
 }
 
+impl RouteHandlerInput for http::HeaderMap {}
+
+/// Describes the responses a handler's return type can produce.
+pub trait RouteHandlerOutput {
+    fn describe(cx: &mut TypeContext, route: &mut RouteSpec) {
+        let _ = cx;
+        let _ = route;
+    }
+}
+
+impl RouteHandlerOutput for () {
+    fn describe(_: &mut TypeContext, route: &mut RouteSpec) {
+        route.responses.push(ResponseSpec {
+            status: Some(StatusCode::OK),
+            description: None,
+            type_desc: None,
+            media_type: None,
+            example: None,
+        });
+    }
+}
+
+impl RouteHandlerOutput for String {
+    fn describe(cx: &mut TypeContext, route: &mut RouteSpec) {
+        route.responses.push(ResponseSpec {
+            status: Some(StatusCode::OK),
+            description: None,
+            type_desc: Some(cx.reflect_as::<String>(Direction::Output)),
+            media_type: Some("text/plain".into()),
+            example: None,
+        });
+    }
+}
+
+impl RouteHandlerOutput for &'static str {
+    fn describe(cx: &mut TypeContext, route: &mut RouteSpec) {
+        <String as RouteHandlerOutput>::describe(cx, route);
+    }
+}
+
+impl RouteHandlerOutput for StatusCode {
+    fn describe(_: &mut TypeContext, route: &mut RouteSpec) {
+        route.responses.push(ResponseSpec {
+            status: None,
+            description: None,
+            type_desc: None,
+            media_type: None,
+            example: None,
+        });
+    }
+}
+
+impl<T: RouteHandlerOutput> RouteHandlerOutput for (StatusCode, T) {
+    fn describe(cx: &mut TypeContext, route: &mut RouteSpec) {
+        // The status overrides whatever `T` would respond with.
+        let start = route.responses.len();
+        T::describe(cx, route);
+        for response in &mut route.responses[start..] {
+            response.status = None;
+        }
+    }
+}
+
+impl<T: RouteHandlerOutput> RouteHandlerOutput for Option<T> {
+    fn describe(cx: &mut TypeContext, route: &mut RouteSpec) {
+        T::describe(cx, route);
+    }
+}
+
+impl<T: RouteHandlerOutput, E: RouteHandlerOutput> RouteHandlerOutput for Result<T, E> {
+    fn describe(cx: &mut TypeContext, route: &mut RouteSpec) {
+        T::describe(cx, route);
+        E::describe(cx, route);
+    }
+}
+
 pub fn spec() -> ApiSpec {
     let mut tcx = TypeContext::new();
 
@@ -118,8 +267,17 @@ pub fn spec() -> ApiSpec {
         routes.push(f(&mut tcx));
     }
 
-    ApiSpec {
-        routes,
-        types: tcx.into_types(),
+    // Types reflected as inputs or outputs may have been merged back under a single id.
+    let (types, ids) = tcx.into_parts();
+    for route in &mut routes {
+        for ty in route.types_mut() {
+            ty.visit_ids(&mut |id| {
+                if let Some(new_id) = ids.get(id) {
+                    *id = new_id.clone();
+                }
+            });
+        }
     }
+
+    ApiSpec { routes, types }
 }
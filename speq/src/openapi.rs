@@ -0,0 +1,383 @@
+use std::collections::{BTreeMap, HashMap};
+
+use http::Method;
+use serde_json::{json, Map, Value};
+
+use crate::json_schema::{self, Dialect, NameConflict, NamingStrategy};
+use crate::reflection::Type;
+use crate::{ApiSpec, RouteSpec, SpeqStr};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Version {
+    /// OpenAPI 3.0.3, for tooling that predates JSON Schema alignment.
+    V3_0,
+    #[default]
+    V3_1,
+}
+
+#[derive(Clone, Debug)]
+pub struct Options {
+    pub title: SpeqStr,
+    pub version: SpeqStr,
+    pub description: Option<SpeqStr>,
+    pub target: Version,
+    pub naming: NamingStrategy,
+}
+
+impl Options {
+    pub fn new(title: impl Into<SpeqStr>, version: impl Into<SpeqStr>) -> Options {
+        Options {
+            title: title.into(),
+            version: version.into(),
+            description: None,
+            target: Version::default(),
+            naming: NamingStrategy::default(),
+        }
+    }
+}
+
+pub fn document(spec: &ApiSpec, options: &Options) -> Result<Value, NameConflict> {
+    let dialect = match options.target {
+        Version::V3_0 => Dialect::OpenApi3_0,
+        Version::V3_1 => Dialect::Draft2020_12,
+    };
+
+    let names = options.naming.names(&spec.types)?;
+    let generator = Generator {
+        schemas: json_schema::Generator::new(&spec.types, &names, dialect, "#/components/schemas/"),
+        version: options.target,
+    };
+
+    let mut info = json!({
+        "title": options.title,
+        "version": options.version,
+    });
+
+    if let Some(description) = &options.description {
+        info["description"] = json!(description);
+    }
+
+    let mut paths = BTreeMap::<String, Map<String, Value>>::new();
+    for route in &spec.routes {
+        let Some(method) = method_key(&route.method) else {
+            continue;
+        };
+
+        let (path, _) = parse_path(&route.path.value);
+
+        paths
+            .entry(path)
+            .or_default()
+            .insert(method.to_owned(), generator.operation(route));
+    }
+
+    let mut schemas = BTreeMap::new();
+    for (id, decl) in &spec.types {
+        generator
+            .schemas
+            .decl_schemas(&names[id], decl, &mut schemas);
+    }
+
+    let openapi = match options.target {
+        Version::V3_0 => "3.0.3",
+        Version::V3_1 => "3.1.0",
+    };
+
+    Ok(json!({
+        "openapi": openapi,
+        "info": info,
+        "paths": paths,
+        "components": {
+            "schemas": schemas,
+        },
+    }))
+}
+
+fn method_key(method: &Method) -> Option<&'static str> {
+    let key = match *method {
+        Method::GET => "get",
+        Method::POST => "post",
+        Method::PUT => "put",
+        Method::DELETE => "delete",
+        Method::HEAD => "head",
+        Method::OPTIONS => "options",
+        Method::PATCH => "patch",
+        Method::TRACE => "trace",
+        _ => return None,
+    };
+    Some(key)
+}
+
+/// Converts a route path into OpenAPI's templated form, returning it along with the names of
+/// its parameters. Both `{param}` and the older `:param`/`*param` syntax are understood.
+fn parse_path(path: &str) -> (String, Vec<String>) {
+    let mut params = vec![];
+    let segments = path
+        .split('/')
+        .map(|segment| {
+            let name =
+                if let Some(name) = segment.strip_prefix('{').and_then(|s| s.strip_suffix('}')) {
+                    name.trim_start_matches('*')
+                } else if let Some(name) = segment
+                    .strip_prefix(':')
+                    .or_else(|| segment.strip_prefix('*'))
+                {
+                    name
+                } else {
+                    return segment.to_owned();
+                };
+
+            params.push(name.to_owned());
+            format!("{{{name}}}")
+        })
+        .collect::<Vec<_>>();
+
+    (segments.join("/"), params)
+}
+
+struct Generator<'a> {
+    schemas: json_schema::Generator<'a>,
+    version: Version,
+}
+
+impl Generator<'_> {
+    fn operation(&self, route: &RouteSpec) -> Value {
+        let mut operation = json!({
+            "operationId": route.name,
+        });
+
+        if let Some(doc) = &route.doc {
+            let doc = doc.lines().map(str::trim).collect::<Vec<_>>().join("\n");
+            let doc = doc.trim();
+            if let Some(summary) = doc.lines().next() {
+                operation["summary"] = json!(summary);
+            }
+            operation["description"] = json!(doc);
+        }
+
+        let parameters = self.parameters(route);
+        if !parameters.is_empty() {
+            operation["parameters"] = json!(parameters);
+        }
+
+        if let Some(request) = &route.request {
+            operation["requestBody"] = json!({
+                "required": !request.is_optional,
+                "content": {
+                    request.media_type.as_ref(): {
+                        "schema": self.schemas.schema(&request.type_desc),
+                    },
+                },
+            });
+        }
+
+        let mut responses = self.responses(route);
+
+        // Responses are mandatory in OpenAPI 3.0, so fall back to an undescribed default.
+        if responses.is_empty() && self.version == Version::V3_0 {
+            responses.insert("default".to_owned(), json!({ "description": "" }));
+        }
+
+        if !responses.is_empty() {
+            operation["responses"] = json!(responses);
+        }
+
+        operation
+    }
+
+    /// Returns the responses of a route by status. Responses with the same status are merged,
+    /// with the schemas of any that share a media type combined. Those given explicitly have
+    /// already replaced any inferred with the same status and media type (see
+    /// `RouteSpec::override_responses`), so only responses of the same kind are combined.
+    fn responses(&self, route: &RouteSpec) -> BTreeMap<String, Value> {
+        let mut responses = BTreeMap::<String, Response>::new();
+        for response in &route.responses {
+            let status = match response.status {
+                Some(status) => status.as_u16().to_string(),
+                None => "default".to_owned(),
+            };
+            let merged = responses.entry(status).or_default();
+
+            // Later descriptions take precedence, so that those given explicitly replace any
+            // inferred from a handler's return type.
+            if response.description.is_some() || merged.description.is_none() {
+                merged.description = response.description.as_deref().or(match response.status {
+                    Some(status) => status.canonical_reason(),
+                    None => Some("Default response"),
+                });
+            }
+
+            let media_type = match (&response.media_type, &response.type_desc) {
+                (Some(media_type), _) => media_type.as_ref(),
+                (None, Some(_)) => "application/json",
+                (None, None) => continue,
+            };
+            let content = merged.content.entry(media_type).or_default();
+
+            if let Some(type_desc) = &response.type_desc {
+                let schema = self.schemas.schema(type_desc);
+                if !content.schemas.contains(&schema) {
+                    content.schemas.push(schema);
+                }
+            }
+
+            if let Some(example) = &response.example {
+                content.example = Some(example);
+            }
+        }
+
+        responses
+            .into_iter()
+            .map(|(status, response)| {
+                let mut value = json!({ "description": response.description.unwrap_or_default() });
+
+                let mut content = Map::new();
+                for (
+                    media_type,
+                    MediaType {
+                        mut schemas,
+                        example,
+                    },
+                ) in response.content
+                {
+                    let mut media_type_value = json!({});
+                    match schemas.len() {
+                        0 => {}
+                        1 => media_type_value["schema"] = schemas.remove(0),
+                        _ => media_type_value["schema"] = json!({ "anyOf": schemas }),
+                    }
+                    if let Some(example) = example {
+                        media_type_value["example"] = example.clone();
+                    }
+                    content.insert(media_type.to_owned(), media_type_value);
+                }
+
+                if !content.is_empty() {
+                    value["content"] = Value::Object(content);
+                }
+
+                (status, value)
+            })
+            .collect()
+    }
+
+    fn parameters(&self, route: &RouteSpec) -> Vec<Value> {
+        let mut parameters = vec![];
+
+        let (_, path_params) = parse_path(&route.path.value);
+        let mut path_types = HashMap::new();
+        match route
+            .path
+            .params
+            .as_ref()
+            .map(|ty| self.schemas.resolve(ty))
+        {
+            Some(Type::Tuple(types)) => {
+                path_types.extend(
+                    path_params
+                        .iter()
+                        .map(String::as_str)
+                        .zip(types.iter().map(|ty| (self.schemas.schema(ty), None))),
+                );
+            }
+            Some(ty) => {
+                if let Some(fields) = self.schemas.struct_fields(ty) {
+                    for (field, _) in fields {
+                        path_types.insert(
+                            field.name.as_ref(),
+                            (self.schemas.constrained_schema(field), field.doc.as_ref()),
+                        );
+                    }
+                } else if let Some(name) = path_params.first() {
+                    path_types.insert(name.as_str(), (self.schemas.schema(ty), None));
+                }
+            }
+            None => {}
+        }
+
+        for name in &path_params {
+            let (schema, doc) = match path_types.remove(name.as_str()) {
+                Some(param) => param,
+                None => (json!({ "type": "string" }), None),
+            };
+
+            let mut parameter = json!({
+                "name": name,
+                "in": "path",
+                "required": true,
+                "schema": schema,
+            });
+
+            if let Some(doc) = doc {
+                parameter["description"] = json!(doc);
+            }
+
+            parameters.push(parameter);
+        }
+
+        if let Some(query) = &route.query {
+            if let Some(fields) = self.schemas.struct_fields(&query.type_desc) {
+                for (field, required) in fields {
+                    if field.read_only {
+                        continue;
+                    }
+
+                    let mut parameter = json!({
+                        "name": field.name,
+                        "in": "query",
+                        "required": required && !query.is_optional,
+                        "schema": self.schemas.constrained_schema(field),
+                    });
+
+                    if let Some(doc) = &field.doc {
+                        parameter["description"] = json!(doc);
+                    }
+
+                    if self.schemas.is_object(&field.type_desc) {
+                        parameter["style"] = json!("deepObject");
+                        parameter["explode"] = json!(true);
+                    }
+
+                    parameters.push(parameter);
+                }
+            } else {
+                parameters.push(json!({
+                    "name": "query",
+                    "in": "query",
+                    "required": !query.is_optional,
+                    "style": "form",
+                    "explode": true,
+                    "schema": self.schemas.schema(&query.type_desc),
+                }));
+            }
+        }
+
+        for header in &route.headers {
+            let schema = match &header.type_desc {
+                Some(ty) => self.schemas.schema(ty),
+                None => json!({ "type": "string" }),
+            };
+
+            parameters.push(json!({
+                "name": header.name,
+                "in": "header",
+                "required": !header.is_optional,
+                "schema": schema,
+            }));
+        }
+
+        parameters
+    }
+}
+
+#[derive(Default)]
+struct Response<'a> {
+    description: Option<&'a str>,
+    content: BTreeMap<&'a str, MediaType<'a>>,
+}
+
+#[derive(Default)]
+struct MediaType<'a> {
+    schemas: Vec<Value>,
+    example: Option<&'a Value>,
+}
@@ -1,30 +1,170 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 use crate::SpeqStr;
 
+/// Which way a type's data flows, for types whose serde attributes make them read differently
+/// from how they are written.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Direction {
+    /// Data that is deserialized, such as a request body.
+    Input,
+    /// Data that is serialized, such as a response body.
+    Output,
+}
+
+impl Direction {
+    fn as_str(self) -> &'static str {
+        match self {
+            Direction::Input => "Input",
+            Direction::Output => "Output",
+        }
+    }
+}
+
 #[doc(hidden)]
 #[derive(Clone, Debug)]
 pub struct TypeContext {
     types: HashMap<SpeqStr, TypeDecl>,
+    pending: HashSet<SpeqStr>,
+    direction: Option<Direction>,
+    /// The type ids and directions of declarations made while reflecting in a direction.
+    directional: HashMap<SpeqStr, (SpeqStr, Direction)>,
 }
 
 impl TypeContext {
     pub fn new() -> TypeContext {
         TypeContext {
             types: HashMap::new(),
+            pending: HashSet::new(),
+            direction: None,
+            directional: HashMap::new(),
         }
     }
 
-    pub fn insert_with(&mut self, id: SpeqStr, f: impl FnOnce(&mut Self) -> TypeDecl) {
-        #[allow(clippy::map_entry)]
-        if !self.types.contains_key(&id) {
-            let decl = f(self);
-            self.types.insert(id, decl);
+    /// The direction types are currently being reflected in, or `None` if their schemas should
+    /// cover both.
+    pub fn direction(&self) -> Option<Direction> {
+        self.direction
+    }
+
+    /// Reflects `T` as it is read or written.
+    pub fn reflect_as<T: Reflect + ?Sized>(&mut self, direction: Direction) -> Type {
+        let prev = self.direction.replace(direction);
+        let ty = T::reflect(self);
+        self.direction = prev;
+        ty
+    }
+
+    /// Declares the type with the given id, if it hasn't been already, returning the id to refer
+    /// to it by.
+    pub fn insert_with(&mut self, id: SpeqStr, f: impl FnOnce(&mut Self) -> TypeDecl) -> SpeqStr {
+        let id = match self.direction {
+            Some(direction) => {
+                let directional_id = SpeqStr::from(format!("{id}::{}", direction.as_str()));
+                self.directional
+                    .insert(directional_id.clone(), (id, direction));
+                directional_id
+            }
+            None => id,
+        };
+
+        // The id is reserved while the declaration is being built, so that recursive references
+        // back to the type resolve to its id instead of building it again.
+        if self.types.contains_key(&id) || !self.pending.insert(id.clone()) {
+            return id;
         }
+
+        let decl = f(self);
+        self.pending.remove(&id);
+        self.types.insert(id.clone(), decl);
+        id
     }
 
+    /// Returns the declared types. Types reflected with `reflect_as` may end up declared under
+    /// different ids than were returned for them, which `into_parts` maps.
     pub fn into_types(self) -> HashMap<SpeqStr, TypeDecl> {
-        self.types
+        self.into_parts().0
+    }
+
+    /// Returns the declared types, along with the ids that types reflected in a direction ended
+    /// up declared under, keyed by the ids `reflect_as` returned for them. Types that were
+    /// reflected in both directions keep separate declarations only if they (or the types they
+    /// refer to) differ.
+    pub fn into_parts(self) -> (HashMap<SpeqStr, TypeDecl>, HashMap<SpeqStr, SpeqStr>) {
+        let TypeContext {
+            mut types,
+            directional,
+            ..
+        } = self;
+
+        let mut by_direction = HashMap::<&SpeqStr, HashMap<Direction, &SpeqStr>>::new();
+        for (directional_id, (id, direction)) in &directional {
+            by_direction
+                .entry(id)
+                .or_default()
+                .insert(*direction, directional_id);
+        }
+
+        // Start by assuming every type can be merged, then split those whose declarations differ
+        // until nothing changes, since splitting one type can make those referring to it differ.
+        let mut merged = by_direction
+            .keys()
+            .filter(|id| !types.contains_key(**id))
+            .copied()
+            .collect::<HashSet<_>>();
+
+        let canonical = |merged: &HashSet<&SpeqStr>, id: &SpeqStr| match directional.get(id) {
+            Some((id, _)) if merged.contains(id) => id.clone(),
+            _ => id.clone(),
+        };
+
+        loop {
+            let split = merged
+                .iter()
+                .copied()
+                .filter(|id| {
+                    let ids = &by_direction[id];
+                    let (Some(input), Some(output)) =
+                        (ids.get(&Direction::Input), ids.get(&Direction::Output))
+                    else {
+                        return false;
+                    };
+
+                    let mut input = types[*input].clone();
+                    let mut output = types[*output].clone();
+                    input.visit_ids(&mut |id| *id = canonical(&merged, id));
+                    output.visit_ids(&mut |id| *id = canonical(&merged, id));
+                    input != output
+                })
+                .collect::<Vec<_>>();
+
+            if split.is_empty() {
+                break;
+            }
+
+            for id in split {
+                merged.remove(id);
+            }
+        }
+
+        let mut result = HashMap::new();
+        for (id, mut decl) in types.drain() {
+            decl.visit_ids(&mut |id| *id = canonical(&merged, id));
+            if let Some((base, direction)) = directional.get(&id) {
+                if !merged.contains(base) {
+                    let name = decl.name_mut();
+                    *name = format!("{name}{}", direction.as_str()).into();
+                }
+            }
+            result.insert(canonical(&merged, &id), decl);
+        }
+
+        let ids = directional
+            .keys()
+            .map(|id| (id.clone(), canonical(&merged, id)))
+            .collect();
+
+        (result, ids)
     }
 }
 
@@ -36,16 +176,49 @@ impl Default for TypeContext {
 
 pub trait Reflect {
     fn type_id() -> Option<SpeqStr>;
+
+    /// A readable name for the type, used when naming instantiations of generic types.
+    fn type_name() -> SpeqStr {
+        short_type_name(std::any::type_name::<Self>()).into()
+    }
+
     fn reflect(cx: &mut TypeContext) -> Type;
 }
 
-#[derive(Clone, Debug)]
+/// Strips module paths from a fully qualified type name, e.g. turning
+/// `alloc::vec::Vec<my_crate::User>` into `Vec<User>`.
+fn short_type_name(name: &str) -> String {
+    let mut short = String::with_capacity(name.len());
+    let mut segment_start = 0;
+    for (i, c) in name.char_indices() {
+        if c == ':' {
+            segment_start = i + 1;
+        } else if !(c.is_alphanumeric() || c == '_') {
+            short.push_str(&name[segment_start..i]);
+            short.push(c);
+            segment_start = i + c.len_utf8();
+        }
+    }
+    short.push_str(&name[segment_start..]);
+    short
+}
+
+#[derive(Clone, Debug, PartialEq)]
 pub enum Type {
     Primitive(PrimitiveType),
+    Formatted(PrimitiveType, Format),
+    /// Free-form data, such as a `serde_json::Value`.
+    Any,
     Option(Box<Type>),
     Array(Box<Type>),
+    /// An array whose elements are unique, such as a `HashSet`.
+    Set(Box<Type>),
+    FixedArray(Box<Type>, usize),
     Tuple(Vec<Type>),
-    Map(Box<Type>),
+    Map {
+        key: Box<Type>,
+        value: Box<Type>,
+    },
     Id(SpeqStr),
 }
 
@@ -57,12 +230,47 @@ impl Type {
             None
         }
     }
+
+    /// Applies `format` to this type if it is a primitive, or to the primitive it contains if it
+    /// is an option or array. Other types are returned unchanged.
+    pub fn with_format(self, format: Format) -> Type {
+        match self {
+            Type::Primitive(primitive) | Type::Formatted(primitive, _) => {
+                Type::Formatted(primitive, format)
+            }
+            Type::Option(inner) => Type::Option(Box::new(inner.with_format(format))),
+            Type::Array(inner) => Type::Array(Box::new(inner.with_format(format))),
+            Type::Set(inner) => Type::Set(Box::new(inner.with_format(format))),
+            Type::FixedArray(inner, len) => {
+                Type::FixedArray(Box::new(inner.with_format(format)), len)
+            }
+            ty => ty,
+        }
+    }
+
+    /// Calls `f` with each type id this type refers to, such as to replace those returned by
+    /// `TypeContext::reflect_as` with the ids from `TypeContext::into_parts`.
+    pub fn visit_ids(&mut self, f: &mut impl FnMut(&mut SpeqStr)) {
+        match self {
+            Type::Primitive(_) | Type::Formatted(..) | Type::Any => {}
+            Type::Option(ty) | Type::Array(ty) | Type::Set(ty) | Type::FixedArray(ty, _) => {
+                ty.visit_ids(f)
+            }
+            Type::Tuple(types) => types.iter_mut().for_each(|ty| ty.visit_ids(f)),
+            Type::Map { key, value } => {
+                key.visit_ids(f);
+                value.visit_ids(f);
+            }
+            Type::Id(id) => f(id),
+        }
+    }
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq)]
 pub enum TypeDecl {
     Struct(StructType),
     Enum(EnumType),
+    Alias(AliasType),
 }
 
 impl TypeDecl {
@@ -73,9 +281,50 @@ impl TypeDecl {
             None
         }
     }
+
+    pub fn name(&self) -> &str {
+        match self {
+            TypeDecl::Struct(decl) => &decl.name,
+            TypeDecl::Enum(decl) => &decl.name,
+            TypeDecl::Alias(decl) => &decl.name,
+        }
+    }
+
+    fn name_mut(&mut self) -> &mut SpeqStr {
+        match self {
+            TypeDecl::Struct(decl) => &mut decl.name,
+            TypeDecl::Enum(decl) => &mut decl.name,
+            TypeDecl::Alias(decl) => &mut decl.name,
+        }
+    }
+
+    fn visit_ids(&mut self, f: &mut impl FnMut(&mut SpeqStr)) {
+        let visit_fields = |fields: &mut Vec<Field>, f: &mut _| {
+            for field in fields {
+                field.type_desc.visit_ids(f);
+            }
+        };
+
+        match self {
+            TypeDecl::Struct(decl) => visit_fields(&mut decl.fields, f),
+            TypeDecl::Alias(decl) => decl.type_desc.visit_ids(f),
+            TypeDecl::Enum(decl) => {
+                for variant in &mut decl.variants {
+                    match &mut variant.kind {
+                        EnumVariantKind::Unit => {}
+                        EnumVariantKind::NewType(ty) => ty.visit_ids(f),
+                        EnumVariantKind::Tuple(types) => {
+                            types.iter_mut().for_each(|ty| ty.visit_ids(f))
+                        }
+                        EnumVariantKind::Struct(fields) => visit_fields(fields, f),
+                    }
+                }
+            }
+        }
+    }
 }
 
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum IntWidth {
     W8 = 8,
     W16 = 16,
@@ -90,7 +339,7 @@ impl IntWidth {
     }
 }
 
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 #[repr(u8)]
 pub enum FloatWidth {
     F32 = 32,
@@ -103,58 +352,200 @@ impl FloatWidth {
     }
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq)]
 pub enum PrimitiveType {
     Bool,
     Int(IntWidth),
     UInt(IntWidth),
     Float(FloatWidth),
+    Char,
     String,
 }
 
-#[derive(Clone, Debug)]
+/// A semantic format refining a primitive type, such as a string holding a UUID.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Format {
+    /// An RFC 3339 full-date, e.g. `2024-01-31`.
+    Date,
+    /// An RFC 3339 date-time, e.g. `2024-01-31T12:00:00Z`.
+    DateTime,
+    /// An RFC 3339 full-time, e.g. `12:00:00Z`.
+    Time,
+    /// A time without a UTC offset, e.g. `12:00:00`.
+    LocalTime,
+    /// A date-time without a UTC offset, e.g. `2024-01-31T12:00:00`.
+    LocalDateTime,
+    /// An RFC 3339 duration, e.g. `P1DT12H`.
+    Duration,
+    Email,
+    Hostname,
+    Ipv4,
+    Ipv6,
+    Uuid,
+    Uri,
+    UriReference,
+    /// A decimal number encoded as a string to preserve precision.
+    Decimal,
+    /// Base64-encoded binary data.
+    Byte,
+    /// Raw binary data, such as a file upload.
+    Binary,
+    /// A string whose value should be obscured by tooling.
+    Password,
+    /// An integer number of seconds since the Unix epoch.
+    UnixTime,
+    Other(SpeqStr),
+}
+
+impl Format {
+    pub fn as_str(&self) -> &str {
+        match self {
+            Format::Date => "date",
+            Format::DateTime => "date-time",
+            Format::Time => "time",
+            Format::LocalTime => "local-time",
+            Format::LocalDateTime => "local-date-time",
+            Format::Duration => "duration",
+            Format::Email => "email",
+            Format::Hostname => "hostname",
+            Format::Ipv4 => "ipv4",
+            Format::Ipv6 => "ipv6",
+            Format::Uuid => "uuid",
+            Format::Uri => "uri",
+            Format::UriReference => "uri-reference",
+            Format::Decimal => "decimal",
+            Format::Byte => "byte",
+            Format::Binary => "binary",
+            Format::Password => "password",
+            Format::UnixTime => "unix-time",
+            Format::Other(format) => format,
+        }
+    }
+}
+
+impl From<&'static str> for Format {
+    fn from(format: &'static str) -> Self {
+        match format {
+            "date" => Format::Date,
+            "date-time" => Format::DateTime,
+            "time" => Format::Time,
+            "local-time" => Format::LocalTime,
+            "local-date-time" => Format::LocalDateTime,
+            "duration" => Format::Duration,
+            "email" => Format::Email,
+            "hostname" => Format::Hostname,
+            "ipv4" => Format::Ipv4,
+            "ipv6" => Format::Ipv6,
+            "uuid" => Format::Uuid,
+            "uri" => Format::Uri,
+            "uri-reference" => Format::UriReference,
+            "decimal" => Format::Decimal,
+            "byte" => Format::Byte,
+            "binary" => Format::Binary,
+            "password" => Format::Password,
+            "unix-time" => Format::UnixTime,
+            format => Format::Other(format.into()),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
 pub struct Field {
     pub name: SpeqStr,
+    pub doc: Option<SpeqStr>,
+    /// Other names the field is accepted under when deserializing.
+    pub aliases: Vec<SpeqStr>,
     pub flatten: bool,
     pub required: bool,
+    /// Whether the field is only ever serialized.
+    pub read_only: bool,
+    /// Whether the field is only ever deserialized.
+    pub write_only: bool,
+    pub constraints: Constraints,
+    /// An example of the field's value, as serialized.
+    pub example: Option<serde_json::Value>,
     pub type_desc: Type,
 }
 
-#[derive(Clone, Debug)]
+/// Constraints on the values a field accepts.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Constraints {
+    /// The minimum value of a number, or the minimum length of a string, array or object.
+    pub min: Option<f64>,
+    /// The maximum value of a number, or the maximum length of a string, array or object.
+    pub max: Option<f64>,
+    /// A regular expression that strings must match.
+    pub pattern: Option<SpeqStr>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
 pub struct StructType {
     pub name: SpeqStr,
+    pub doc: Option<SpeqStr>,
+    pub example: Option<serde_json::Value>,
     pub fields: Vec<Field>,
 }
 
-#[derive(Clone, Debug)]
+/// A named declaration that is represented on the wire by another type, such as a newtype
+/// struct.
+#[derive(Clone, Debug, PartialEq)]
+pub struct AliasType {
+    pub name: SpeqStr,
+    pub doc: Option<SpeqStr>,
+    pub example: Option<serde_json::Value>,
+    pub type_desc: Type,
+}
+
+#[derive(Clone, Debug, PartialEq)]
 pub enum EnumTag {
     External,
     Internal(SpeqStr),
     Adjacent { tag: SpeqStr, content: SpeqStr },
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq)]
 pub struct EnumVariant {
     pub name: SpeqStr,
+    pub doc: Option<SpeqStr>,
     pub tag_value: SpeqStr,
+    /// Other tag values the variant is accepted under when deserializing.
+    pub aliases: Vec<SpeqStr>,
+    /// Whether unknown tag values deserialize to this variant, as with `#[serde(other)]`.
+    pub other: bool,
     pub kind: EnumVariantKind,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq)]
 pub enum EnumVariantKind {
     Unit,
     NewType(Type),
+    Tuple(Vec<Type>),
     Struct(Vec<Field>),
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq)]
 pub struct EnumType {
     pub name: SpeqStr,
+    pub doc: Option<SpeqStr>,
+    pub example: Option<serde_json::Value>,
     pub tag: Option<EnumTag>,
     pub variants: Vec<EnumVariant>,
 }
 
 mod impls {
+    use std::borrow::Cow;
+    use std::cell::{Cell, RefCell};
+    use std::cmp::Reverse;
+    use std::collections::{BTreeMap, BTreeSet, BinaryHeap, LinkedList, VecDeque};
+    use std::marker::PhantomData;
+    use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, SocketAddrV4, SocketAddrV6};
+    use std::num::*;
+    use std::path::{Path, PathBuf};
+    use std::rc::Rc;
+    use std::sync::atomic::*;
+    use std::sync::{Arc, Mutex, RwLock};
+    use std::time::{Duration, SystemTime};
+
     use super::*;
 
     macro_rules! impl_for_primitive {
@@ -171,24 +562,80 @@ mod impls {
         };
     }
 
+    macro_rules! impl_for_formatted {
+        ($t:ty, $p:expr, $f:expr) => {
+            impl Reflect for $t {
+                fn type_id() -> Option<SpeqStr> {
+                    None
+                }
+
+                fn reflect(_: &mut TypeContext) -> Type {
+                    Type::Formatted($p, $f)
+                }
+            }
+        };
+    }
+
     impl_for_primitive!(i8, PrimitiveType::Int(IntWidth::W8));
     impl_for_primitive!(i16, PrimitiveType::Int(IntWidth::W16));
     impl_for_primitive!(i32, PrimitiveType::Int(IntWidth::W32));
     impl_for_primitive!(i64, PrimitiveType::Int(IntWidth::W64));
     impl_for_primitive!(i128, PrimitiveType::Int(IntWidth::W128));
+    impl_for_primitive!(isize, PrimitiveType::Int(IntWidth::W64));
 
     impl_for_primitive!(u8, PrimitiveType::UInt(IntWidth::W8));
     impl_for_primitive!(u16, PrimitiveType::UInt(IntWidth::W16));
     impl_for_primitive!(u32, PrimitiveType::UInt(IntWidth::W32));
     impl_for_primitive!(u64, PrimitiveType::UInt(IntWidth::W64));
     impl_for_primitive!(u128, PrimitiveType::UInt(IntWidth::W128));
+    impl_for_primitive!(usize, PrimitiveType::UInt(IntWidth::W64));
+
+    impl_for_primitive!(NonZeroI8, PrimitiveType::Int(IntWidth::W8));
+    impl_for_primitive!(NonZeroI16, PrimitiveType::Int(IntWidth::W16));
+    impl_for_primitive!(NonZeroI32, PrimitiveType::Int(IntWidth::W32));
+    impl_for_primitive!(NonZeroI64, PrimitiveType::Int(IntWidth::W64));
+    impl_for_primitive!(NonZeroI128, PrimitiveType::Int(IntWidth::W128));
+    impl_for_primitive!(NonZeroIsize, PrimitiveType::Int(IntWidth::W64));
+
+    impl_for_primitive!(NonZeroU8, PrimitiveType::UInt(IntWidth::W8));
+    impl_for_primitive!(NonZeroU16, PrimitiveType::UInt(IntWidth::W16));
+    impl_for_primitive!(NonZeroU32, PrimitiveType::UInt(IntWidth::W32));
+    impl_for_primitive!(NonZeroU64, PrimitiveType::UInt(IntWidth::W64));
+    impl_for_primitive!(NonZeroU128, PrimitiveType::UInt(IntWidth::W128));
+    impl_for_primitive!(NonZeroUsize, PrimitiveType::UInt(IntWidth::W64));
+
+    impl_for_primitive!(AtomicI8, PrimitiveType::Int(IntWidth::W8));
+    impl_for_primitive!(AtomicI16, PrimitiveType::Int(IntWidth::W16));
+    impl_for_primitive!(AtomicI32, PrimitiveType::Int(IntWidth::W32));
+    impl_for_primitive!(AtomicI64, PrimitiveType::Int(IntWidth::W64));
+    impl_for_primitive!(AtomicIsize, PrimitiveType::Int(IntWidth::W64));
+
+    impl_for_primitive!(AtomicU8, PrimitiveType::UInt(IntWidth::W8));
+    impl_for_primitive!(AtomicU16, PrimitiveType::UInt(IntWidth::W16));
+    impl_for_primitive!(AtomicU32, PrimitiveType::UInt(IntWidth::W32));
+    impl_for_primitive!(AtomicU64, PrimitiveType::UInt(IntWidth::W64));
+    impl_for_primitive!(AtomicUsize, PrimitiveType::UInt(IntWidth::W64));
 
     impl_for_primitive!(f32, PrimitiveType::Float(FloatWidth::F32));
     impl_for_primitive!(f64, PrimitiveType::Float(FloatWidth::F64));
 
     impl_for_primitive!(bool, PrimitiveType::Bool);
+    impl_for_primitive!(AtomicBool, PrimitiveType::Bool);
+
+    impl_for_primitive!(char, PrimitiveType::Char);
+    impl_for_primitive!(str, PrimitiveType::String);
     impl_for_primitive!(String, PrimitiveType::String);
 
+    // serde writes these as strings for human readable formats like JSON.
+    impl_for_primitive!(IpAddr, PrimitiveType::String);
+    impl_for_formatted!(Ipv4Addr, PrimitiveType::String, Format::Ipv4);
+    impl_for_formatted!(Ipv6Addr, PrimitiveType::String, Format::Ipv6);
+    impl_for_primitive!(SocketAddr, PrimitiveType::String);
+    impl_for_primitive!(SocketAddrV4, PrimitiveType::String);
+    impl_for_primitive!(SocketAddrV6, PrimitiveType::String);
+    impl_for_primitive!(Path, PrimitiveType::String);
+    impl_for_primitive!(PathBuf, PrimitiveType::String);
+
     macro_rules! impl_for_tuple {
         ($($t:ident)*) => {
             #[allow(unused)]
@@ -207,6 +654,7 @@ mod impls {
     impl_for_tuple!();
     impl_for_tuple!(A);
     impl_for_tuple!(A B);
+    impl_for_tuple!(A B C);
     impl_for_tuple!(A B C D);
     impl_for_tuple!(A B C D E);
     impl_for_tuple!(A B C D E F);
@@ -227,24 +675,221 @@ mod impls {
         }
     }
 
-    impl<T: Reflect> Reflect for Vec<T> {
+    macro_rules! impl_for_collection {
+        ($variant:ident, $type:ty) => {
+            impl<T: Reflect> Reflect for $type {
+                fn type_id() -> Option<SpeqStr> {
+                    None
+                }
+
+                fn reflect(cx: &mut TypeContext) -> Type {
+                    Type::$variant(Box::new(T::reflect(cx)))
+                }
+            }
+        };
+    }
+
+    impl_for_collection!(Array, [T]);
+    impl_for_collection!(Array, Vec<T>);
+    impl_for_collection!(Array, VecDeque<T>);
+    impl_for_collection!(Array, LinkedList<T>);
+    impl_for_collection!(Array, BinaryHeap<T>);
+    impl_for_collection!(Set, BTreeSet<T>);
+
+    impl<T: Reflect, S> Reflect for HashSet<T, S> {
+        fn type_id() -> Option<SpeqStr> {
+            None
+        }
+
+        fn reflect(cx: &mut TypeContext) -> Type {
+            Type::Set(Box::new(T::reflect(cx)))
+        }
+    }
+
+    impl<T: Reflect, const N: usize> Reflect for [T; N] {
         fn type_id() -> Option<SpeqStr> {
             None
         }
 
         fn reflect(cx: &mut TypeContext) -> Type {
-            Type::Array(Box::new(T::reflect(cx)))
+            Type::FixedArray(Box::new(T::reflect(cx)), N)
+        }
+    }
+
+    impl<T: ?Sized> Reflect for PhantomData<T> {
+        fn type_id() -> Option<SpeqStr> {
+            None
+        }
+
+        fn reflect(cx: &mut TypeContext) -> Type {
+            <()>::reflect(cx)
+        }
+    }
+
+    impl<T: Reflect, E: Reflect> Reflect for Result<T, E> {
+        fn type_id() -> Option<SpeqStr> {
+            Some(
+                format!(
+                    "core::result::Result<{}, {}>",
+                    std::any::type_name::<T>(),
+                    std::any::type_name::<E>(),
+                )
+                .into(),
+            )
+        }
+
+        fn type_name() -> SpeqStr {
+            format!("Result<{}, {}>", T::type_name(), E::type_name()).into()
+        }
+
+        fn reflect(cx: &mut TypeContext) -> Type {
+            let id = Self::type_id().unwrap();
+            let id = cx.insert_with(id, |cx| {
+                TypeDecl::Enum(EnumType {
+                    name: Self::type_name(),
+                    doc: None,
+                    example: None,
+                    tag: Some(EnumTag::External),
+                    variants: vec![
+                        EnumVariant {
+                            name: "Ok".into(),
+                            doc: None,
+                            tag_value: "Ok".into(),
+                            aliases: vec![],
+                            other: false,
+                            kind: EnumVariantKind::NewType(T::reflect(cx)),
+                        },
+                        EnumVariant {
+                            name: "Err".into(),
+                            doc: None,
+                            tag_value: "Err".into(),
+                            aliases: vec![],
+                            other: false,
+                            kind: EnumVariantKind::NewType(E::reflect(cx)),
+                        },
+                    ],
+                })
+            });
+            Type::Id(id)
+        }
+    }
+
+    fn struct_field(name: &'static str, type_desc: Type) -> Field {
+        Field {
+            name: name.into(),
+            doc: None,
+            aliases: vec![],
+            flatten: false,
+            required: true,
+            read_only: false,
+            write_only: false,
+            constraints: Constraints::default(),
+            example: None,
+            type_desc,
+        }
+    }
+
+    impl Reflect for Duration {
+        fn type_id() -> Option<SpeqStr> {
+            Some(SpeqStr::Borrowed("core::time::Duration"))
+        }
+
+        fn reflect(cx: &mut TypeContext) -> Type {
+            let id = Self::type_id().unwrap();
+            let id = cx.insert_with(id, |cx| {
+                TypeDecl::Struct(StructType {
+                    name: "Duration".into(),
+                    doc: None,
+                    example: None,
+                    fields: vec![
+                        struct_field("secs", u64::reflect(cx)),
+                        struct_field("nanos", u32::reflect(cx)),
+                    ],
+                })
+            });
+            Type::Id(id)
+        }
+    }
+
+    impl Reflect for SystemTime {
+        fn type_id() -> Option<SpeqStr> {
+            Some(SpeqStr::Borrowed("std::time::SystemTime"))
+        }
+
+        fn reflect(cx: &mut TypeContext) -> Type {
+            let id = Self::type_id().unwrap();
+            let id = cx.insert_with(id, |cx| {
+                TypeDecl::Struct(StructType {
+                    name: "SystemTime".into(),
+                    doc: None,
+                    example: None,
+                    fields: vec![
+                        struct_field("secs_since_epoch", u64::reflect(cx)),
+                        struct_field("nanos_since_epoch", u32::reflect(cx)),
+                    ],
+                })
+            });
+            Type::Id(id)
+        }
+    }
+
+    impl<K: Reflect, V: Reflect, S> Reflect for HashMap<K, V, S> {
+        fn type_id() -> Option<SpeqStr> {
+            None
+        }
+
+        fn reflect(cx: &mut TypeContext) -> Type {
+            Type::Map {
+                key: Box::new(K::reflect(cx)),
+                value: Box::new(V::reflect(cx)),
+            }
+        }
+    }
+
+    impl<K: Reflect, V: Reflect> Reflect for BTreeMap<K, V> {
+        fn type_id() -> Option<SpeqStr> {
+            None
+        }
+
+        fn reflect(cx: &mut TypeContext) -> Type {
+            Type::Map {
+                key: Box::new(K::reflect(cx)),
+                value: Box::new(V::reflect(cx)),
+            }
+        }
+    }
+
+    #[cfg(feature = "indexmap")]
+    impl<K: Reflect, V: Reflect, S> Reflect for indexmap::IndexMap<K, V, S> {
+        fn type_id() -> Option<SpeqStr> {
+            None
+        }
+
+        fn reflect(cx: &mut TypeContext) -> Type {
+            Type::Map {
+                key: Box::new(K::reflect(cx)),
+                value: Box::new(V::reflect(cx)),
+            }
         }
     }
 
-    #[allow(unused)]
     macro_rules! forward_impl {
+        (?Sized $type:ty) => {
+            forward_impl!(@impl [+ ?Sized] $type);
+        };
         ($type:ty) => {
-            impl<T: Reflect> Reflect for $type {
+            forward_impl!(@impl [] $type);
+        };
+        (@impl [$($bound:tt)*] $type:ty) => {
+            impl<T: Reflect $($bound)*> Reflect for $type {
                 fn type_id() -> Option<SpeqStr> {
                     T::type_id()
                 }
 
+                fn type_name() -> SpeqStr {
+                    T::type_name()
+                }
+
                 fn reflect(cx: &mut TypeContext) -> Type {
                     T::reflect(cx)
                 }
@@ -252,6 +897,186 @@ mod impls {
         };
     }
 
+    forward_impl!(?Sized &T);
+    forward_impl!(?Sized &mut T);
+    forward_impl!(?Sized Box<T>);
+    forward_impl!(?Sized Rc<T>);
+    forward_impl!(?Sized Arc<T>);
+    forward_impl!(?Sized RefCell<T>);
+    forward_impl!(?Sized Mutex<T>);
+    forward_impl!(?Sized RwLock<T>);
+    forward_impl!(Cell<T>);
+    forward_impl!(Wrapping<T>);
+    forward_impl!(Reverse<T>);
+
+    impl<T: Reflect + ToOwned + ?Sized> Reflect for Cow<'_, T> {
+        fn type_id() -> Option<SpeqStr> {
+            T::type_id()
+        }
+
+        fn type_name() -> SpeqStr {
+            T::type_name()
+        }
+
+        fn reflect(cx: &mut TypeContext) -> Type {
+            T::reflect(cx)
+        }
+    }
+
+    impl Reflect for serde_json::Value {
+        fn type_id() -> Option<SpeqStr> {
+            None
+        }
+
+        fn reflect(_: &mut TypeContext) -> Type {
+            Type::Any
+        }
+    }
+
+    impl Reflect for serde_json::Map<String, serde_json::Value> {
+        fn type_id() -> Option<SpeqStr> {
+            None
+        }
+
+        fn reflect(cx: &mut TypeContext) -> Type {
+            Type::Map {
+                key: Box::new(String::reflect(cx)),
+                value: Box::new(serde_json::Value::reflect(cx)),
+            }
+        }
+    }
+
+    #[cfg(feature = "bytes")]
+    impl Reflect for bytes::Bytes {
+        fn type_id() -> Option<SpeqStr> {
+            None
+        }
+
+        fn reflect(cx: &mut TypeContext) -> Type {
+            <[u8]>::reflect(cx)
+        }
+    }
+
+    #[cfg(feature = "bytes")]
+    impl Reflect for bytes::BytesMut {
+        fn type_id() -> Option<SpeqStr> {
+            None
+        }
+
+        fn reflect(cx: &mut TypeContext) -> Type {
+            <[u8]>::reflect(cx)
+        }
+    }
+
+    #[cfg(feature = "chrono")]
+    impl<Tz: chrono::TimeZone> Reflect for chrono::DateTime<Tz> {
+        fn type_id() -> Option<SpeqStr> {
+            None
+        }
+
+        fn reflect(_: &mut TypeContext) -> Type {
+            Type::Formatted(PrimitiveType::String, Format::DateTime)
+        }
+    }
+
+    #[cfg(feature = "chrono")]
+    impl_for_formatted!(chrono::NaiveDate, PrimitiveType::String, Format::Date);
+    #[cfg(feature = "chrono")]
+    impl_for_formatted!(chrono::NaiveTime, PrimitiveType::String, Format::LocalTime);
+    #[cfg(feature = "chrono")]
+    impl_for_formatted!(
+        chrono::NaiveDateTime,
+        PrimitiveType::String,
+        Format::LocalDateTime
+    );
+
+    #[cfg(all(feature = "time", not(feature = "time-human-readable")))]
+    macro_rules! impl_as {
+        ($t:ty, $as:ty) => {
+            impl Reflect for $t {
+                fn type_id() -> Option<SpeqStr> {
+                    None
+                }
+
+                fn reflect(cx: &mut TypeContext) -> Type {
+                    <$as>::reflect(cx)
+                }
+            }
+        };
+    }
+
+    // By default, time serializes these as tuples of their components, e.g. a date as its year and
+    // ordinal day. If time's `serde-human-readable` feature is enabled, which changes how every
+    // value is serialized so isn't enabled here, the `time-human-readable` feature describes its
+    // strings instead. Those date-times aren't RFC 3339 (they are separated by a space, and
+    // offsets include seconds), so they are only known to be strings unless overridden, as with
+    // `time::serde::rfc3339`.
+    #[cfg(all(feature = "time", not(feature = "time-human-readable")))]
+    impl_as!(time::Date, (i32, u16));
+    #[cfg(all(feature = "time", not(feature = "time-human-readable")))]
+    impl_as!(time::Time, (u8, u8, u8, u32));
+    #[cfg(all(feature = "time", not(feature = "time-human-readable")))]
+    impl_as!(time::PrimitiveDateTime, (i32, u16, u8, u8, u8, u32));
+    #[cfg(all(feature = "time", not(feature = "time-human-readable")))]
+    impl_as!(
+        time::OffsetDateTime,
+        (i32, u16, u8, u8, u8, u32, i8, i8, i8)
+    );
+    #[cfg(all(feature = "time", not(feature = "time-human-readable")))]
+    impl_as!(time::UtcOffset, (i8, i8, i8));
+
+    #[cfg(feature = "time-human-readable")]
+    impl_for_formatted!(time::Date, PrimitiveType::String, Format::Date);
+    #[cfg(feature = "time-human-readable")]
+    impl_for_formatted!(time::Time, PrimitiveType::String, Format::LocalTime);
+    #[cfg(feature = "time-human-readable")]
+    impl_for_primitive!(time::PrimitiveDateTime, PrimitiveType::String);
+    #[cfg(feature = "time-human-readable")]
+    impl_for_primitive!(time::OffsetDateTime, PrimitiveType::String);
+    #[cfg(feature = "time-human-readable")]
+    impl_for_primitive!(time::UtcOffset, PrimitiveType::String);
+
+    #[cfg(feature = "uuid")]
+    impl_for_formatted!(uuid::Uuid, PrimitiveType::String, Format::Uuid);
+
+    #[cfg(feature = "url")]
+    impl_for_formatted!(url::Url, PrimitiveType::String, Format::Uri);
+
+    #[cfg(feature = "rust_decimal")]
+    impl_for_formatted!(
+        rust_decimal::Decimal,
+        PrimitiveType::String,
+        Format::Decimal
+    );
+
+    #[cfg(feature = "semver")]
+    impl_for_formatted!(
+        semver::Version,
+        PrimitiveType::String,
+        Format::Other("semver".into())
+    );
+    #[cfg(feature = "semver")]
+    impl_for_primitive!(semver::VersionReq, PrimitiveType::String);
+
+    #[cfg(feature = "ipnet")]
+    impl_for_formatted!(
+        ipnet::IpNet,
+        PrimitiveType::String,
+        Format::Other("cidr".into())
+    );
+    #[cfg(feature = "ipnet")]
+    impl_for_formatted!(
+        ipnet::Ipv4Net,
+        PrimitiveType::String,
+        Format::Other("cidr".into())
+    );
+    #[cfg(feature = "ipnet")]
+    impl_for_formatted!(
+        ipnet::Ipv6Net,
+        PrimitiveType::String,
+        Format::Other("cidr".into())
+    );
+
     #[cfg(feature = "camino")]
     impl Reflect for camino::Utf8Path {
         fn type_id() -> Option<SpeqStr> {
@@ -0,0 +1,182 @@
+#![cfg(feature = "axum-json")]
+
+use axum::http::StatusCode;
+use axum::response::{Html, IntoResponse};
+use axum::Json;
+use serde::{Deserialize, Serialize};
+use speq::axum::get;
+use speq::reflection::{Reflect, Type};
+use speq::RouteSpec;
+
+speq::axum_config!(());
+
+#[derive(Serialize, Deserialize, speq::Reflect)]
+struct User {
+    name: String,
+}
+
+struct Undescribed;
+
+impl IntoResponse for Undescribed {
+    fn into_response(self) -> axum::response::Response {
+        StatusCode::INTERNAL_SERVER_ERROR.into_response()
+    }
+}
+
+#[derive(speq::RouteHandlerOutput)]
+enum Described {
+    #[response(status = 404)]
+    NotFound,
+}
+
+impl IntoResponse for Described {
+    fn into_response(self) -> axum::response::Response {
+        StatusCode::NOT_FOUND.into_response()
+    }
+}
+
+#[get("/undescribed")]
+async fn undescribed_error() -> Result<Json<User>, Undescribed> {
+    Err(Undescribed)
+}
+
+#[get("/described")]
+async fn described_error() -> Result<Json<User>, Described> {
+    Err(Described::NotFound)
+}
+
+#[get("/opaque")]
+async fn opaque_success() -> Result<impl IntoResponse, Described> {
+    Ok(())
+}
+
+#[get("/page")]
+async fn page() -> Html<&'static str> {
+    Html("")
+}
+
+#[get("/value")]
+#[response(status = 200, description = "The user", model = User)]
+async fn explicit_model() -> Json<serde_json::Value> {
+    Json(serde_json::Value::Null)
+}
+
+#[get("/created")]
+#[response(status = 201, description = "Created")]
+async fn explicit_status() -> (StatusCode, Json<User>) {
+    (StatusCode::CREATED, Json(User { name: "".into() }))
+}
+
+#[get("/csv")]
+#[response(status = 200, description = "The user as CSV", media_type = "text/csv")]
+async fn other_media_type() -> Json<User> {
+    Json(User { name: "".into() })
+}
+
+fn route(name: &str) -> RouteSpec {
+    let spec = speq::spec();
+    spec.routes
+        .into_iter()
+        .find(|route| route.name == name)
+        .unwrap()
+}
+
+fn statuses(route: &RouteSpec) -> Vec<Option<StatusCode>> {
+    route
+        .responses
+        .iter()
+        .map(|response| response.status)
+        .collect()
+}
+
+#[test]
+fn result_with_undescribed_error_keeps_success() {
+    let route = route("undescribed_error");
+    assert_eq!(statuses(&route), [Some(StatusCode::OK)]);
+    assert!(route.responses[0].type_desc.is_some());
+}
+
+#[test]
+fn result_with_described_error() {
+    let route = route("described_error");
+    assert_eq!(
+        statuses(&route),
+        [Some(StatusCode::OK), Some(StatusCode::NOT_FOUND)],
+    );
+}
+
+#[test]
+fn result_with_opaque_success() {
+    let route = route("opaque_success");
+    assert_eq!(statuses(&route), [Some(StatusCode::NOT_FOUND)]);
+}
+
+/// Returns the status, media type and description of each response.
+fn responses(route: &RouteSpec) -> Vec<(Option<StatusCode>, Option<&str>, Option<&str>)> {
+    route
+        .responses
+        .iter()
+        .map(|response| {
+            (
+                response.status,
+                response.media_type.as_deref(),
+                response.description.as_deref(),
+            )
+        })
+        .collect()
+}
+
+#[test]
+fn html_responds_html() {
+    let route = route("page");
+    assert_eq!(
+        responses(&route),
+        [(Some(StatusCode::OK), Some("text/html"), None)],
+    );
+}
+
+#[test]
+fn explicit_response_replaces_inferred() {
+    let route = route("explicit_model");
+    assert_eq!(
+        responses(&route),
+        [(
+            Some(StatusCode::OK),
+            Some("application/json"),
+            Some("The user")
+        )],
+    );
+    assert_eq!(
+        route.responses[0].type_desc,
+        Some(Type::Id(<User as Reflect>::type_id().unwrap())),
+    );
+}
+
+#[test]
+fn explicit_response_replaces_status_less_inferred() {
+    let route = route("explicit_status");
+    assert_eq!(
+        responses(&route),
+        [(
+            Some(StatusCode::CREATED),
+            Some("application/json"),
+            Some("Created")
+        )],
+    );
+}
+
+#[test]
+fn explicit_response_keeps_other_media_types() {
+    let route = route("other_media_type");
+    assert_eq!(
+        responses(&route),
+        [
+            (Some(StatusCode::OK), Some("application/json"), None),
+            (
+                Some(StatusCode::OK),
+                Some("text/csv"),
+                Some("The user as CSV")
+            ),
+        ],
+    );
+}
@@ -0,0 +1,67 @@
+//! Types shared by the tests of each schema dialect.
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+#[derive(Serialize, Deserialize, speq::Reflect)]
+pub struct Plain {
+    name: String,
+}
+
+#[derive(Serialize, Deserialize, speq::Reflect)]
+pub enum External {
+    Unit,
+    NewType(u32),
+    Tuple(u32, String),
+    Struct { a: u32 },
+}
+
+#[derive(Serialize, Deserialize, speq::Reflect)]
+#[serde(tag = "kind")]
+pub enum Internal {
+    A {
+        a: u32,
+    },
+    B(Plain),
+    #[serde(other)]
+    Unknown,
+}
+
+#[derive(Serialize, Deserialize, speq::Reflect)]
+#[serde(tag = "t", content = "c")]
+pub enum Adjacent {
+    A(u32),
+    B { b: String },
+    C,
+}
+
+#[derive(Serialize, Deserialize, speq::Reflect)]
+#[serde(untagged)]
+pub enum Untagged {
+    A(u32),
+    B(String),
+}
+
+#[derive(Serialize, Deserialize, speq::Reflect)]
+pub struct Base {
+    id: u32,
+}
+
+#[derive(Serialize, Deserialize, speq::Reflect)]
+pub struct Flat {
+    #[serde(flatten)]
+    base: Base,
+    #[serde(flatten)]
+    extra: Option<Plain>,
+}
+
+#[derive(Serialize, Deserialize, speq::Reflect)]
+pub struct Nullable {
+    count: Option<u32>,
+    plain: Option<Plain>,
+    pair: (u32, String),
+}
+
+pub fn uint32() -> Value {
+    json!({ "type": "integer", "format": "uint32", "minimum": 0 })
+}
@@ -0,0 +1,233 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use speq::json_schema::{for_type, NamingStrategy};
+use speq::reflection::Reflect;
+
+use common::{uint32, Adjacent, External, Flat, Internal, Nullable, Plain, Untagged};
+
+mod common;
+
+mod other {
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Serialize, Deserialize, speq::Reflect)]
+    pub struct Plain {
+        pub id: u32,
+    }
+}
+
+#[derive(Serialize, Deserialize, speq::Reflect)]
+struct Conflicting {
+    a: Plain,
+    b: other::Plain,
+}
+
+/// Returns the schema declared for `T`, named `name`.
+fn def<T: Reflect>(name: &str) -> Value {
+    for_type::<T>(NamingStrategy::Short).unwrap()["$defs"][name].clone()
+}
+
+#[test]
+fn document_shape() {
+    assert_eq!(
+        for_type::<Plain>(NamingStrategy::Short).unwrap(),
+        json!({
+            "$schema": "https://json-schema.org/draft/2020-12/schema",
+            "$ref": "#/$defs/Plain",
+            "$defs": {
+                "Plain": {
+                    "type": "object",
+                    "properties": { "name": { "type": "string" } },
+                    "required": ["name"],
+                },
+            },
+        }),
+    );
+
+    assert_eq!(
+        for_type::<Vec<u32>>(NamingStrategy::Short).unwrap(),
+        json!({
+            "$schema": "https://json-schema.org/draft/2020-12/schema",
+            "type": "array",
+            "items": uint32(),
+        }),
+    );
+}
+
+#[test]
+fn name_conflicts() {
+    let conflict = for_type::<Conflicting>(NamingStrategy::Short).unwrap_err();
+    assert_eq!(conflict.name, "Plain");
+
+    let schema = for_type::<Conflicting>(NamingStrategy::FullyQualified).unwrap();
+    let defs = schema["$defs"].as_object().unwrap();
+    assert_eq!(defs.len(), 3);
+    assert!(defs.keys().any(|name| name.ends_with("other.Plain")));
+}
+
+#[test]
+fn externally_tagged_enum() {
+    assert_eq!(
+        def::<External>("External"),
+        json!({
+            "oneOf": [
+                { "type": "string", "enum": ["Unit"] },
+                {
+                    "type": "object",
+                    "properties": { "NewType": uint32() },
+                    "required": ["NewType"],
+                    "additionalProperties": false,
+                },
+                {
+                    "type": "object",
+                    "properties": {
+                        "Tuple": {
+                            "type": "array",
+                            "prefixItems": [uint32(), { "type": "string" }],
+                            "items": false,
+                            "minItems": 2,
+                            "maxItems": 2,
+                        },
+                    },
+                    "required": ["Tuple"],
+                    "additionalProperties": false,
+                },
+                {
+                    "type": "object",
+                    "properties": {
+                        "Struct": {
+                            "type": "object",
+                            "properties": { "a": uint32() },
+                            "required": ["a"],
+                        },
+                    },
+                    "required": ["Struct"],
+                    "additionalProperties": false,
+                },
+            ],
+        }),
+    );
+}
+
+#[test]
+fn internally_tagged_enum() {
+    assert_eq!(
+        def::<Internal>("Internal"),
+        json!({
+            "oneOf": [
+                {
+                    "type": "object",
+                    "properties": {
+                        "kind": { "type": "string", "const": "A" },
+                        "a": uint32(),
+                    },
+                    "required": ["kind", "a"],
+                },
+                {
+                    "allOf": [
+                        {
+                            "type": "object",
+                            "properties": { "kind": { "type": "string", "const": "B" } },
+                            "required": ["kind"],
+                        },
+                        { "$ref": "#/$defs/Plain" },
+                    ],
+                },
+                {
+                    "type": "object",
+                    "properties": {
+                        "kind": { "type": "string", "not": { "enum": ["A", "B"] } },
+                    },
+                    "required": ["kind"],
+                },
+            ],
+        }),
+    );
+}
+
+#[test]
+fn adjacently_tagged_enum() {
+    assert_eq!(
+        def::<Adjacent>("Adjacent"),
+        json!({
+            "oneOf": [
+                {
+                    "type": "object",
+                    "properties": {
+                        "t": { "type": "string", "const": "A" },
+                        "c": uint32(),
+                    },
+                    "required": ["t", "c"],
+                },
+                {
+                    "type": "object",
+                    "properties": {
+                        "t": { "type": "string", "const": "B" },
+                        "c": {
+                            "type": "object",
+                            "properties": { "b": { "type": "string" } },
+                            "required": ["b"],
+                        },
+                    },
+                    "required": ["t", "c"],
+                },
+                {
+                    "type": "object",
+                    "properties": { "t": { "type": "string", "const": "C" } },
+                    "required": ["t"],
+                },
+            ],
+        }),
+    );
+}
+
+#[test]
+fn untagged_enum() {
+    assert_eq!(
+        def::<Untagged>("Untagged"),
+        json!({ "anyOf": [uint32(), { "type": "string" }] }),
+    );
+}
+
+#[test]
+fn flattened_option() {
+    assert_eq!(
+        def::<Flat>("Flat"),
+        json!({
+            "type": "object",
+            "properties": {
+                "id": uint32(),
+                "name": { "type": "string" },
+            },
+            "required": ["id"],
+        }),
+    );
+}
+
+#[test]
+fn nullable_and_tuples() {
+    assert_eq!(
+        def::<Nullable>("Nullable"),
+        json!({
+            "type": "object",
+            "properties": {
+                "count": {
+                    "type": ["integer", "null"],
+                    "format": "uint32",
+                    "minimum": 0,
+                },
+                "plain": {
+                    "anyOf": [{ "$ref": "#/$defs/Plain" }, { "type": "null" }],
+                },
+                "pair": {
+                    "type": "array",
+                    "prefixItems": [uint32(), { "type": "string" }],
+                    "items": false,
+                    "minItems": 2,
+                    "maxItems": 2,
+                },
+            },
+            "required": ["pair"],
+        }),
+    );
+}
@@ -0,0 +1,605 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use speq::openapi::{self, Options, Version};
+use speq::reflection::Reflect;
+use speq::{
+    ApiSpec, HeaderSpec, Method, PathSpec, QuerySpec, RequestSpec, ResponseSpec, RouteSpec,
+    StatusCode, TypeContext,
+};
+
+use common::{uint32, Adjacent, External, Flat, Internal, Nullable, Untagged};
+
+mod common;
+
+#[derive(Serialize, Deserialize, speq::Reflect)]
+struct UserPath {
+    /// The user's id.
+    id: u32,
+    slug: String,
+}
+
+#[derive(Serialize, Deserialize, speq::Reflect)]
+struct Range {
+    min: u32,
+    max: u32,
+}
+
+#[derive(Serialize, Deserialize, speq::Reflect)]
+struct Filter {
+    /// The page to return.
+    page: u32,
+    search: Option<String>,
+    range: Range,
+}
+
+fn route(path: &'static str) -> RouteSpec {
+    RouteSpec {
+        name: "route".into(),
+        path: PathSpec {
+            value: path.into(),
+            params: None,
+        },
+        method: Method::GET,
+        src_file: file!().into(),
+        doc: None,
+        headers: vec![],
+        query: None,
+        request: None,
+        responses: vec![],
+    }
+}
+
+fn document(cx: TypeContext, routes: Vec<RouteSpec>, target: Version) -> Value {
+    let spec = ApiSpec {
+        routes,
+        types: cx.into_types(),
+    };
+    let mut options = Options::new("test", "1.0");
+    options.target = target;
+    openapi::document(&spec, &options).unwrap()
+}
+
+fn parameters(cx: TypeContext, route: RouteSpec) -> Value {
+    let document = document(cx, vec![route], Version::V3_1);
+    let (_, path) = document["paths"]
+        .as_object()
+        .unwrap()
+        .iter()
+        .next()
+        .unwrap();
+    path["get"]["parameters"].clone()
+}
+
+/// Returns the 3.0 component schemas declared for `T`.
+fn schemas_3_0<T: Reflect>() -> Value {
+    let mut cx = TypeContext::new();
+    T::reflect(&mut cx);
+    document(cx, vec![], Version::V3_0)["components"]["schemas"].clone()
+}
+
+#[test]
+fn document_shape() {
+    let mut options = Options::new("test", "1.0");
+    options.description = Some("A test API.".into());
+    let spec = ApiSpec {
+        routes: vec![],
+        types: HashMap::new(),
+    };
+
+    assert_eq!(
+        openapi::document(&spec, &options).unwrap(),
+        json!({
+            "openapi": "3.1.0",
+            "info": {
+                "title": "test",
+                "version": "1.0",
+                "description": "A test API.",
+            },
+            "paths": {},
+            "components": { "schemas": {} },
+        }),
+    );
+
+    options.target = Version::V3_0;
+    assert_eq!(
+        openapi::document(&spec, &options).unwrap()["openapi"],
+        "3.0.3",
+    );
+}
+
+#[test]
+fn path_params_from_struct() {
+    let mut cx = TypeContext::new();
+    let mut route = route("/users/{id}/{slug}");
+    route.path.params = Some(UserPath::reflect(&mut cx));
+
+    assert_eq!(
+        parameters(cx, route),
+        json!([
+            {
+                "name": "id",
+                "in": "path",
+                "required": true,
+                "description": "The user's id.",
+                "schema": { "type": "integer", "format": "uint32", "minimum": 0 },
+            },
+            {
+                "name": "slug",
+                "in": "path",
+                "required": true,
+                "schema": { "type": "string" },
+            },
+        ]),
+    );
+}
+
+#[test]
+fn path_params_from_tuple() {
+    let mut cx = TypeContext::new();
+    let mut route = route("/users/:id/posts/:post");
+    route.path.params = Some(<(u32, String)>::reflect(&mut cx));
+
+    assert_eq!(
+        parameters(cx, route),
+        json!([
+            {
+                "name": "id",
+                "in": "path",
+                "required": true,
+                "schema": { "type": "integer", "format": "uint32", "minimum": 0 },
+            },
+            {
+                "name": "post",
+                "in": "path",
+                "required": true,
+                "schema": { "type": "string" },
+            },
+        ]),
+    );
+}
+
+#[test]
+fn untyped_path_params_are_strings() {
+    let cx = TypeContext::new();
+    let route = route("/files/{*path}");
+
+    assert_eq!(
+        parameters(cx, route),
+        json!([
+            {
+                "name": "path",
+                "in": "path",
+                "required": true,
+                "schema": { "type": "string" },
+            },
+        ]),
+    );
+}
+
+#[test]
+fn query_params_from_struct() {
+    let mut cx = TypeContext::new();
+    let mut route = route("/users");
+    route.query = Some(QuerySpec {
+        type_desc: Filter::reflect(&mut cx),
+        is_optional: false,
+    });
+
+    assert_eq!(
+        parameters(cx, route),
+        json!([
+            {
+                "name": "page",
+                "in": "query",
+                "required": true,
+                "description": "The page to return.",
+                "schema": { "type": "integer", "format": "uint32", "minimum": 0 },
+            },
+            {
+                "name": "search",
+                "in": "query",
+                "required": false,
+                "schema": { "type": ["string", "null"] },
+            },
+            {
+                "name": "range",
+                "in": "query",
+                "required": true,
+                "style": "deepObject",
+                "explode": true,
+                "schema": { "$ref": "#/components/schemas/Range" },
+            },
+        ]),
+    );
+}
+
+#[test]
+fn optional_query_params_are_not_required() {
+    let mut cx = TypeContext::new();
+    let mut route = route("/users");
+    route.query = Some(QuerySpec {
+        type_desc: Filter::reflect(&mut cx),
+        is_optional: true,
+    });
+
+    let parameters = parameters(cx, route);
+    for parameter in parameters.as_array().unwrap() {
+        assert_eq!(parameter["required"], false);
+    }
+}
+
+#[test]
+fn query_params_from_map() {
+    let mut cx = TypeContext::new();
+    let mut route = route("/users");
+    route.query = Some(QuerySpec {
+        type_desc: HashMap::<String, String>::reflect(&mut cx),
+        is_optional: false,
+    });
+
+    assert_eq!(
+        parameters(cx, route),
+        json!([
+            {
+                "name": "query",
+                "in": "query",
+                "required": true,
+                "style": "form",
+                "explode": true,
+                "schema": {
+                    "type": "object",
+                    "additionalProperties": { "type": "string" },
+                },
+            },
+        ]),
+    );
+}
+
+#[test]
+fn header_params() {
+    let mut cx = TypeContext::new();
+    let mut route = route("/users");
+    route.headers = vec![
+        HeaderSpec {
+            name: "x-request-id".into(),
+            type_desc: Some(u64::reflect(&mut cx)),
+            is_optional: false,
+        },
+        HeaderSpec {
+            name: "user-agent".into(),
+            type_desc: None,
+            is_optional: true,
+        },
+    ];
+
+    assert_eq!(
+        parameters(cx, route),
+        json!([
+            {
+                "name": "x-request-id",
+                "in": "header",
+                "required": true,
+                "schema": { "type": "integer", "format": "uint64", "minimum": 0 },
+            },
+            {
+                "name": "user-agent",
+                "in": "header",
+                "required": false,
+                "schema": { "type": "string" },
+            },
+        ]),
+    );
+}
+
+#[test]
+fn parameters_are_ordered_path_query_header() {
+    let mut cx = TypeContext::new();
+    let mut route = route("/users/{id}");
+    route.path.params = Some(u32::reflect(&mut cx));
+    route.query = Some(QuerySpec {
+        type_desc: Range::reflect(&mut cx),
+        is_optional: false,
+    });
+    route.headers = vec![HeaderSpec {
+        name: "x-request-id".into(),
+        type_desc: None,
+        is_optional: false,
+    }];
+
+    let parameters = parameters(cx, route);
+    let locations = parameters
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|parameter| (parameter["name"].as_str(), parameter["in"].as_str()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        locations,
+        [
+            (Some("id"), Some("path")),
+            (Some("min"), Some("query")),
+            (Some("max"), Some("query")),
+            (Some("x-request-id"), Some("header")),
+        ],
+    );
+}
+
+#[test]
+fn request_body_and_responses() {
+    let mut cx = TypeContext::new();
+    let mut route = route("/ranges");
+    route.method = Method::POST;
+    route.doc = Some(" Creates a range.\n\n Both ends are inclusive.".into());
+    route.request = Some(RequestSpec {
+        type_desc: Range::reflect(&mut cx),
+        is_optional: false,
+        media_type: "application/json".into(),
+    });
+    route.responses = vec![
+        ResponseSpec {
+            status: Some(StatusCode::CREATED),
+            description: None,
+            type_desc: Some(Range::reflect(&mut cx)),
+            media_type: None,
+            example: None,
+        },
+        ResponseSpec {
+            status: None,
+            description: None,
+            type_desc: None,
+            media_type: None,
+            example: None,
+        },
+    ];
+
+    let document = document(cx, vec![route], Version::V3_1);
+    assert_eq!(
+        document["paths"]["/ranges"]["post"],
+        json!({
+            "operationId": "route",
+            "summary": "Creates a range.",
+            "description": "Creates a range.\n\nBoth ends are inclusive.",
+            "requestBody": {
+                "required": true,
+                "content": {
+                    "application/json": {
+                        "schema": { "$ref": "#/components/schemas/Range" },
+                    },
+                },
+            },
+            "responses": {
+                "201": {
+                    "description": "Created",
+                    "content": {
+                        "application/json": {
+                            "schema": { "$ref": "#/components/schemas/Range" },
+                        },
+                    },
+                },
+                "default": { "description": "Default response" },
+            },
+        }),
+    );
+}
+
+#[test]
+fn responses_are_required_in_3_0() {
+    let v3_0 = document(TypeContext::new(), vec![route("/")], Version::V3_0);
+    assert_eq!(
+        v3_0["paths"]["/"]["get"]["responses"],
+        json!({ "default": { "description": "" } }),
+    );
+
+    let v3_1 = document(TypeContext::new(), vec![route("/")], Version::V3_1);
+    assert_eq!(v3_1["paths"]["/"]["get"].get("responses"), None);
+}
+
+#[test]
+fn externally_tagged_enum_3_0() {
+    assert_eq!(
+        schemas_3_0::<External>()["External"],
+        json!({
+            "oneOf": [
+                { "type": "string", "enum": ["Unit"] },
+                {
+                    "type": "object",
+                    "properties": { "NewType": uint32() },
+                    "required": ["NewType"],
+                    "additionalProperties": false,
+                },
+                {
+                    "type": "object",
+                    "properties": {
+                        "Tuple": {
+                            "type": "array",
+                            "items": { "anyOf": [uint32(), { "type": "string" }] },
+                            "minItems": 2,
+                            "maxItems": 2,
+                        },
+                    },
+                    "required": ["Tuple"],
+                    "additionalProperties": false,
+                },
+                {
+                    "type": "object",
+                    "properties": {
+                        "Struct": {
+                            "type": "object",
+                            "properties": { "a": uint32() },
+                            "required": ["a"],
+                        },
+                    },
+                    "required": ["Struct"],
+                    "additionalProperties": false,
+                },
+            ],
+        }),
+    );
+}
+
+#[test]
+fn internally_tagged_enum_3_0() {
+    let schemas = schemas_3_0::<Internal>();
+    assert_eq!(
+        schemas["Internal"],
+        json!({
+            "oneOf": [
+                { "$ref": "#/components/schemas/Internal.A" },
+                { "$ref": "#/components/schemas/Internal.B" },
+                { "$ref": "#/components/schemas/Internal.Unknown" },
+            ],
+            "discriminator": {
+                "propertyName": "kind",
+                "mapping": {
+                    "A": "#/components/schemas/Internal.A",
+                    "B": "#/components/schemas/Internal.B",
+                },
+            },
+        }),
+    );
+    assert_eq!(
+        schemas["Internal.A"],
+        json!({
+            "type": "object",
+            "properties": {
+                "kind": { "type": "string", "enum": ["A"] },
+                "a": uint32(),
+            },
+            "required": ["kind", "a"],
+        }),
+    );
+    assert_eq!(
+        schemas["Internal.B"],
+        json!({
+            "allOf": [
+                {
+                    "type": "object",
+                    "properties": { "kind": { "type": "string", "enum": ["B"] } },
+                    "required": ["kind"],
+                },
+                { "$ref": "#/components/schemas/Plain" },
+            ],
+        }),
+    );
+    assert_eq!(
+        schemas["Internal.Unknown"],
+        json!({
+            "type": "object",
+            "properties": {
+                "kind": { "type": "string", "not": { "enum": ["A", "B"] } },
+            },
+            "required": ["kind"],
+        }),
+    );
+}
+
+#[test]
+fn adjacently_tagged_enum_3_0() {
+    let schemas = schemas_3_0::<Adjacent>();
+    assert_eq!(
+        schemas["Adjacent"],
+        json!({
+            "oneOf": [
+                { "$ref": "#/components/schemas/Adjacent.A" },
+                { "$ref": "#/components/schemas/Adjacent.B" },
+                { "$ref": "#/components/schemas/Adjacent.C" },
+            ],
+            "discriminator": {
+                "propertyName": "t",
+                "mapping": {
+                    "A": "#/components/schemas/Adjacent.A",
+                    "B": "#/components/schemas/Adjacent.B",
+                    "C": "#/components/schemas/Adjacent.C",
+                },
+            },
+        }),
+    );
+    assert_eq!(
+        schemas["Adjacent.A"],
+        json!({
+            "type": "object",
+            "properties": {
+                "t": { "type": "string", "enum": ["A"] },
+                "c": uint32(),
+            },
+            "required": ["t", "c"],
+        }),
+    );
+    assert_eq!(
+        schemas["Adjacent.B"],
+        json!({
+            "type": "object",
+            "properties": {
+                "t": { "type": "string", "enum": ["B"] },
+                "c": {
+                    "type": "object",
+                    "properties": { "b": { "type": "string" } },
+                    "required": ["b"],
+                },
+            },
+            "required": ["t", "c"],
+        }),
+    );
+    assert_eq!(
+        schemas["Adjacent.C"],
+        json!({
+            "type": "object",
+            "properties": { "t": { "type": "string", "enum": ["C"] } },
+            "required": ["t"],
+        }),
+    );
+}
+
+#[test]
+fn untagged_enum_3_0() {
+    assert_eq!(
+        schemas_3_0::<Untagged>()["Untagged"],
+        json!({ "anyOf": [uint32(), { "type": "string" }] }),
+    );
+}
+
+#[test]
+fn flattened_option_3_0() {
+    assert_eq!(
+        schemas_3_0::<Flat>()["Flat"],
+        json!({
+            "type": "object",
+            "properties": {
+                "id": uint32(),
+                "name": { "type": "string" },
+            },
+            "required": ["id"],
+        }),
+    );
+}
+
+#[test]
+fn nullable_and_tuples_3_0() {
+    assert_eq!(
+        schemas_3_0::<Nullable>()["Nullable"],
+        json!({
+            "type": "object",
+            "properties": {
+                "count": {
+                    "type": "integer",
+                    "format": "uint32",
+                    "minimum": 0,
+                    "nullable": true,
+                },
+                "plain": {
+                    "allOf": [{ "$ref": "#/components/schemas/Plain" }],
+                    "nullable": true,
+                },
+                "pair": {
+                    "type": "array",
+                    "items": { "anyOf": [uint32(), { "type": "string" }] },
+                    "minItems": 2,
+                    "maxItems": 2,
+                },
+            },
+            "required": ["pair"],
+        }),
+    );
+}
@@ -0,0 +1,733 @@
+use std::sync::LazyLock;
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use speq::reflection::{Constraints, Direction, Format, Reflect, StructType, Type, TypeDecl};
+use speq::{
+    RequestSpec, ResponseSpec, RouteHandlerOutput, RouteSpec, SpeqStr, StatusCode, TypeContext,
+};
+use validator::{Validate, ValidationError};
+
+#[derive(Serialize, Deserialize, speq::Reflect)]
+struct Plain {
+    name: String,
+}
+
+#[derive(Serialize, Deserialize, speq::Reflect)]
+struct Inner {
+    #[serde(skip_deserializing)]
+    id: u32,
+    name: String,
+}
+
+#[derive(Serialize, Deserialize, speq::Reflect)]
+struct Outer {
+    inner: Inner,
+    count: u32,
+}
+
+#[derive(Serialize, Deserialize, speq::Reflect)]
+struct Tree {
+    children: Vec<Tree>,
+}
+
+#[derive(Serialize, Deserialize, speq::Reflect)]
+struct Node {
+    #[serde(skip_deserializing)]
+    id: u32,
+    children: Vec<Node>,
+}
+
+fn id<T: Reflect>() -> SpeqStr {
+    T::type_id().unwrap()
+}
+
+fn directional_id<T: Reflect>(direction: &str) -> SpeqStr {
+    format!("{}::{direction}", id::<T>()).into()
+}
+
+fn struct_type<'a>(
+    types: &'a std::collections::HashMap<SpeqStr, TypeDecl>,
+    id: &str,
+) -> &'a StructType {
+    match &types[id] {
+        TypeDecl::Struct(decl) => decl,
+        decl => panic!("expected a struct, got {decl:?}"),
+    }
+}
+
+#[test]
+fn single_direction_keeps_base_id() {
+    let mut cx = TypeContext::new();
+    let mut ty = cx.reflect_as::<Plain>(Direction::Input);
+    assert_eq!(ty, Type::Id(directional_id::<Plain>("Input")));
+
+    let (types, ids) = cx.into_parts();
+    assert_eq!(types.len(), 1);
+    ty.visit_ids(&mut |id| *id = ids[id].clone());
+    assert_eq!(ty, Type::Id(id::<Plain>()));
+    assert_eq!(struct_type(&types, &id::<Plain>()).name, "Plain");
+}
+
+#[test]
+fn split_ids_are_mapped() {
+    let mut cx = TypeContext::new();
+    let input = cx.reflect_as::<Outer>(Direction::Input);
+    let output = cx.reflect_as::<Outer>(Direction::Output);
+
+    let (types, ids) = cx.into_parts();
+    for (mut ty, direction) in [(input, "Input"), (output, "Output")] {
+        ty.visit_ids(&mut |id| *id = ids[id].clone());
+        let Type::Id(id) = ty else {
+            panic!("expected an id, got {ty:?}");
+        };
+        assert_eq!(struct_type(&types, &id).name, format!("Outer{direction}"));
+    }
+}
+
+#[test]
+fn identical_directions_merge() {
+    let mut cx = TypeContext::new();
+    cx.reflect_as::<Plain>(Direction::Input);
+    cx.reflect_as::<Plain>(Direction::Output);
+
+    let types = cx.into_types();
+    assert_eq!(types.len(), 1);
+    assert_eq!(struct_type(&types, &id::<Plain>()).name, "Plain");
+}
+
+#[test]
+fn differing_nested_type_splits_both() {
+    let mut cx = TypeContext::new();
+    cx.reflect_as::<Outer>(Direction::Input);
+    cx.reflect_as::<Outer>(Direction::Output);
+
+    let types = cx.into_types();
+    assert_eq!(types.len(), 4);
+    assert!(!types.contains_key(&id::<Outer>()));
+    assert!(!types.contains_key(&id::<Inner>()));
+
+    for direction in ["Input", "Output"] {
+        let outer = struct_type(&types, &directional_id::<Outer>(direction));
+        assert_eq!(outer.name, format!("Outer{direction}"));
+        assert_eq!(
+            outer.fields[0].type_desc,
+            Type::Id(directional_id::<Inner>(direction)),
+        );
+
+        let inner = struct_type(&types, &directional_id::<Inner>(direction));
+        assert_eq!(inner.name, format!("Inner{direction}"));
+    }
+
+    let input = struct_type(&types, &directional_id::<Inner>("Input"));
+    assert_eq!(input.fields.len(), 1);
+    let output = struct_type(&types, &directional_id::<Inner>("Output"));
+    assert_eq!(output.fields.len(), 2);
+}
+
+#[test]
+fn recursive_type_merges() {
+    let mut cx = TypeContext::new();
+    cx.reflect_as::<Tree>(Direction::Input);
+    cx.reflect_as::<Tree>(Direction::Output);
+
+    let types = cx.into_types();
+    assert_eq!(types.len(), 1);
+    let tree = struct_type(&types, &id::<Tree>());
+    assert_eq!(tree.name, "Tree");
+    assert_eq!(
+        tree.fields[0].type_desc,
+        Type::Array(Box::new(Type::Id(id::<Tree>()))),
+    );
+}
+
+#[test]
+fn recursive_type_splits() {
+    let mut cx = TypeContext::new();
+    cx.reflect_as::<Node>(Direction::Input);
+    cx.reflect_as::<Node>(Direction::Output);
+
+    let types = cx.into_types();
+    assert_eq!(types.len(), 2);
+    for direction in ["Input", "Output"] {
+        let id = directional_id::<Node>(direction);
+        let node = struct_type(&types, &id);
+        assert_eq!(node.name, format!("Node{direction}"));
+        let children = node.fields.last().unwrap();
+        assert_eq!(children.type_desc, Type::Array(Box::new(Type::Id(id))));
+    }
+}
+
+fn tree_route(cx: &mut TypeContext) -> RouteSpec {
+    RouteSpec {
+        name: "tree".into(),
+        path: speq::PathSpec {
+            value: "/tree".into(),
+            params: None,
+        },
+        method: speq::Method::POST,
+        src_file: file!().into(),
+        doc: None,
+        headers: vec![],
+        query: None,
+        request: Some(RequestSpec {
+            type_desc: cx.reflect_as::<Tree>(Direction::Input),
+            is_optional: false,
+            media_type: "application/json".into(),
+        }),
+        responses: vec![ResponseSpec {
+            status: Some(StatusCode::OK),
+            description: None,
+            type_desc: Some(cx.reflect_as::<Vec<Tree>>(Direction::Output)),
+            media_type: Some("application/json".into()),
+            example: None,
+        }],
+    }
+}
+
+speq::inventory::submit!(speq::RouteSpecFn(tree_route));
+
+#[test]
+fn route_ids_are_rewritten() {
+    let spec = speq::spec();
+    assert_eq!(spec.types.len(), 1);
+    assert!(spec.types.contains_key(&id::<Tree>()));
+
+    let route = &spec.routes[0];
+    assert_eq!(
+        route.request.as_ref().unwrap().type_desc,
+        Type::Id(id::<Tree>()),
+    );
+    assert_eq!(
+        route.responses[0].type_desc,
+        Some(Type::Array(Box::new(Type::Id(id::<Tree>())))),
+    );
+}
+
+fn empty_route() -> RouteSpec {
+    RouteSpec {
+        name: "empty".into(),
+        path: speq::PathSpec {
+            value: "/".into(),
+            params: None,
+        },
+        method: speq::Method::GET,
+        src_file: file!().into(),
+        doc: None,
+        headers: vec![],
+        query: None,
+        request: None,
+        responses: vec![],
+    }
+}
+
+fn described<T: RouteHandlerOutput>() -> RouteSpec {
+    let mut route = empty_route();
+    T::describe(&mut TypeContext::new(), &mut route);
+    route
+}
+
+fn response(status: Option<StatusCode>, media_type: &'static str) -> ResponseSpec {
+    ResponseSpec {
+        status,
+        description: Some("Explicit".into()),
+        type_desc: None,
+        media_type: Some(media_type.into()),
+        example: None,
+    }
+}
+
+/// Returns the status, media type and description of each response.
+fn responses(route: &RouteSpec) -> Vec<(Option<StatusCode>, Option<&str>, Option<&str>)> {
+    route
+        .responses
+        .iter()
+        .map(|response| {
+            (
+                response.status,
+                response.media_type.as_deref(),
+                response.description.as_deref(),
+            )
+        })
+        .collect()
+}
+
+#[test]
+fn unit_responds_ok() {
+    assert_eq!(
+        responses(&described::<()>()),
+        [(Some(StatusCode::OK), None, None)],
+    );
+}
+
+#[test]
+fn string_responds_text() {
+    let route = described::<String>();
+    assert_eq!(
+        responses(&route),
+        [(Some(StatusCode::OK), Some("text/plain"), None)],
+    );
+    assert_eq!(
+        route.responses[0].type_desc,
+        Some(String::reflect(&mut TypeContext::new())),
+    );
+    assert_eq!(responses(&described::<&'static str>()), responses(&route),);
+}
+
+#[test]
+fn status_code_responses_have_no_status() {
+    assert_eq!(responses(&described::<StatusCode>()), [(None, None, None)],);
+    assert_eq!(
+        responses(&described::<(StatusCode, String)>()),
+        [(None, Some("text/plain"), None)],
+    );
+}
+
+#[test]
+fn explicit_response_replaces_inferred() {
+    let mut route = described::<String>();
+    route.override_responses([response(Some(StatusCode::OK), "text/plain")]);
+    assert_eq!(
+        responses(&route),
+        [(Some(StatusCode::OK), Some("text/plain"), Some("Explicit"))],
+    );
+}
+
+#[test]
+fn explicit_response_replaces_status_less_inferred() {
+    let mut route = described::<(StatusCode, String)>();
+    route.override_responses([response(Some(StatusCode::CREATED), "text/plain")]);
+    assert_eq!(
+        responses(&route),
+        [(
+            Some(StatusCode::CREATED),
+            Some("text/plain"),
+            Some("Explicit")
+        )],
+    );
+}
+
+#[test]
+fn explicit_status_only_response_keeps_inferred_content() {
+    let mut route = described::<(StatusCode, String)>();
+    route.override_responses([ResponseSpec {
+        status: Some(StatusCode::CREATED),
+        description: Some("Explicit".into()),
+        type_desc: None,
+        media_type: None,
+        example: None,
+    }]);
+    assert_eq!(
+        responses(&route),
+        [(
+            Some(StatusCode::CREATED),
+            Some("text/plain"),
+            Some("Explicit")
+        )],
+    );
+    assert!(route.responses[0].type_desc.is_some());
+}
+
+#[test]
+fn explicit_response_keeps_other_media_types() {
+    let mut route = described::<String>();
+    route.override_responses([
+        response(Some(StatusCode::OK), "text/csv"),
+        response(Some(StatusCode::NOT_FOUND), "text/plain"),
+    ]);
+    assert_eq!(
+        responses(&route),
+        [
+            (Some(StatusCode::OK), Some("text/plain"), None),
+            (Some(StatusCode::OK), Some("text/csv"), Some("Explicit")),
+            (
+                Some(StatusCode::NOT_FOUND),
+                Some("text/plain"),
+                Some("Explicit")
+            ),
+        ],
+    );
+}
+
+#[derive(Serialize, Deserialize, speq::Reflect)]
+#[serde(from = "RawCelsius")]
+struct Celsius {
+    degrees: f64,
+}
+
+#[derive(Deserialize, speq::Reflect)]
+struct RawCelsius(f64);
+
+impl From<RawCelsius> for Celsius {
+    fn from(RawCelsius(degrees): RawCelsius) -> Self {
+        Celsius { degrees }
+    }
+}
+
+#[derive(Serialize, Deserialize, speq::Reflect)]
+#[serde(from = "String")]
+struct Marker;
+
+impl From<String> for Marker {
+    fn from(_: String) -> Self {
+        Marker
+    }
+}
+
+fn decl<T: Reflect>(direction: Option<Direction>) -> TypeDecl {
+    let mut cx = TypeContext::new();
+    let mut ty = match direction {
+        Some(direction) => cx.reflect_as::<T>(direction),
+        None => T::reflect(&mut cx),
+    };
+    let (mut types, ids) = cx.into_parts();
+    ty.visit_ids(&mut |id| *id = ids.get(id).unwrap_or(id).clone());
+    let Type::Id(id) = ty else {
+        panic!("expected an id, got {ty:?}");
+    };
+    types.remove(&id).unwrap()
+}
+
+#[test]
+fn from_describes_input_only() {
+    assert!(matches!(decl::<Celsius>(None), TypeDecl::Struct(_)));
+    assert!(matches!(
+        decl::<Celsius>(Some(Direction::Output)),
+        TypeDecl::Struct(_),
+    ));
+
+    let TypeDecl::Alias(alias) = decl::<Celsius>(Some(Direction::Input)) else {
+        panic!("expected an alias");
+    };
+    assert_eq!(alias.type_desc, Type::Id(id::<RawCelsius>()));
+}
+
+#[test]
+fn from_describes_types_without_members() {
+    let TypeDecl::Alias(alias) = decl::<Marker>(None) else {
+        panic!("expected an alias");
+    };
+    assert_eq!(alias.type_desc, String::reflect(&mut TypeContext::new()));
+}
+
+#[allow(dead_code)]
+#[derive(Serialize, Deserialize, speq::Reflect)]
+struct Flags {
+    plain: u32,
+    #[serde(skip)]
+    skipped: u32,
+    #[serde(skip_serializing)]
+    write_only: u32,
+    #[serde(skip_deserializing)]
+    read_only: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    maybe: Option<u32>,
+    #[serde(default)]
+    defaulted: u32,
+    #[serde(rename(serialize = "out", deserialize = "in"))]
+    renamed: u32,
+    #[serde(alias = "old")]
+    aliased: u32,
+}
+
+#[derive(Default, Serialize, Deserialize, speq::Reflect)]
+#[serde(default)]
+struct Defaults {
+    a: u32,
+    #[serde(skip_deserializing)]
+    b: u32,
+}
+
+#[derive(Serialize, Deserialize, speq::Reflect)]
+#[serde(transparent)]
+struct Wrapper {
+    inner: u32,
+}
+
+#[allow(dead_code)]
+#[derive(Serialize, Deserialize, speq::Reflect)]
+#[serde(tag = "kind")]
+enum Kinds {
+    #[serde(alias = "first")]
+    A,
+    #[serde(rename(serialize = "b_out", deserialize = "b_in"))]
+    B,
+    #[serde(skip_deserializing)]
+    C,
+    #[serde(other)]
+    Unknown,
+}
+
+/// Returns the name, `required`, `read_only`, `write_only` and aliases of each field.
+fn field_flags(decl: TypeDecl) -> Vec<(String, bool, bool, bool, Vec<String>)> {
+    let TypeDecl::Struct(decl) = decl else {
+        panic!("expected a struct, got {decl:?}");
+    };
+    decl.fields
+        .into_iter()
+        .map(|field| {
+            (
+                field.name.into_owned(),
+                field.required,
+                field.read_only,
+                field.write_only,
+                field.aliases.into_iter().map(SpeqStr::into_owned).collect(),
+            )
+        })
+        .collect()
+}
+
+/// Returns the tag value, aliases and `other` of each variant.
+fn variant_flags(decl: TypeDecl) -> Vec<(String, Vec<String>, bool)> {
+    let TypeDecl::Enum(decl) = decl else {
+        panic!("expected an enum, got {decl:?}");
+    };
+    decl.variants
+        .into_iter()
+        .map(|variant| {
+            (
+                variant.tag_value.into_owned(),
+                variant
+                    .aliases
+                    .into_iter()
+                    .map(SpeqStr::into_owned)
+                    .collect(),
+                variant.other,
+            )
+        })
+        .collect()
+}
+
+fn flag(
+    name: &str,
+    required: bool,
+    read_only: bool,
+    write_only: bool,
+    aliases: &[&str],
+) -> (String, bool, bool, bool, Vec<String>) {
+    (
+        name.to_owned(),
+        required,
+        read_only,
+        write_only,
+        aliases.iter().map(|alias| alias.to_string()).collect(),
+    )
+}
+
+#[test]
+fn field_flags_without_direction() {
+    assert_eq!(
+        field_flags(decl::<Flags>(None)),
+        [
+            flag("plain", true, false, false, &[]),
+            flag("write_only", false, false, true, &[]),
+            flag("read_only", true, true, false, &[]),
+            flag("maybe", false, false, false, &[]),
+            flag("defaulted", false, false, false, &[]),
+            flag("out", true, false, false, &["in"]),
+            flag("aliased", true, false, false, &["old"]),
+        ],
+    );
+}
+
+#[test]
+fn field_flags_as_input() {
+    assert_eq!(
+        field_flags(decl::<Flags>(Some(Direction::Input))),
+        [
+            flag("plain", true, false, false, &[]),
+            flag("write_only", true, false, false, &[]),
+            flag("maybe", true, false, false, &[]),
+            flag("defaulted", false, false, false, &[]),
+            flag("in", true, false, false, &[]),
+            flag("aliased", true, false, false, &["old"]),
+        ],
+    );
+}
+
+#[test]
+fn field_flags_as_output() {
+    assert_eq!(
+        field_flags(decl::<Flags>(Some(Direction::Output))),
+        [
+            flag("plain", true, false, false, &[]),
+            flag("read_only", true, false, false, &[]),
+            flag("maybe", false, false, false, &[]),
+            flag("defaulted", true, false, false, &[]),
+            flag("out", true, false, false, &[]),
+            flag("aliased", true, false, false, &[]),
+        ],
+    );
+}
+
+#[test]
+fn container_default() {
+    assert_eq!(
+        field_flags(decl::<Defaults>(None)),
+        [
+            flag("a", false, false, false, &[]),
+            flag("b", true, true, false, &[]),
+        ],
+    );
+    assert_eq!(
+        field_flags(decl::<Defaults>(Some(Direction::Input))),
+        [flag("a", false, false, false, &[])],
+    );
+    assert_eq!(
+        field_flags(decl::<Defaults>(Some(Direction::Output))),
+        [
+            flag("a", true, false, false, &[]),
+            flag("b", true, false, false, &[]),
+        ],
+    );
+}
+
+#[test]
+fn transparent_container() {
+    let TypeDecl::Alias(alias) = decl::<Wrapper>(None) else {
+        panic!("expected an alias");
+    };
+    assert_eq!(alias.type_desc, u32::reflect(&mut TypeContext::new()));
+}
+
+#[test]
+fn variant_flags_by_direction() {
+    let variant = |tag: &str, aliases: &[&str], other| {
+        (
+            tag.to_owned(),
+            aliases
+                .iter()
+                .map(|alias| alias.to_string())
+                .collect::<Vec<_>>(),
+            other,
+        )
+    };
+
+    assert_eq!(
+        variant_flags(decl::<Kinds>(None)),
+        [
+            variant("A", &["first"], false),
+            variant("b_out", &["b_in"], false),
+            variant("C", &[], false),
+            variant("Unknown", &[], true),
+        ],
+    );
+    assert_eq!(
+        variant_flags(decl::<Kinds>(Some(Direction::Input))),
+        [
+            variant("A", &["first"], false),
+            variant("b_in", &[], false),
+            variant("Unknown", &[], true),
+        ],
+    );
+    assert_eq!(
+        variant_flags(decl::<Kinds>(Some(Direction::Output))),
+        [
+            variant("A", &[], false),
+            variant("b_out", &[], false),
+            variant("C", &[], false),
+            variant("Unknown", &[], false),
+        ],
+    );
+}
+
+static SLUG: LazyLock<Regex> = LazyLock::new(|| Regex::new("^[a-z-]+$").unwrap());
+
+fn validate_slug(_: &str) -> Result<(), ValidationError> {
+    Ok(())
+}
+
+#[derive(Serialize, Deserialize, Validate, speq::Reflect)]
+struct Address {
+    #[validate(length(min = 1))]
+    street: String,
+}
+
+#[derive(Serialize, Deserialize, Validate, speq::Reflect)]
+struct Validated {
+    #[validate(length(min = 1, max = 10))]
+    name: String,
+    #[validate(length(equal = 3, message = "must be three characters"))]
+    code: String,
+    #[validate(range(min = -1.5, max = 2.5))]
+    ratio: f64,
+    #[validate(range(max = 100))]
+    percent: u8,
+    #[validate(email)]
+    email: String,
+    #[validate(url(message = "must be a url"))]
+    homepage: String,
+    #[validate(custom(function = "validate_slug"))]
+    custom: String,
+    #[validate(regex(path = *SLUG))]
+    slug: String,
+    #[validate(nested)]
+    address: Address,
+    #[validate(custom(function = "validate_slug"), length(max = 5))]
+    combined: String,
+}
+
+#[test]
+fn validator_constraints() {
+    let TypeDecl::Struct(decl) = decl::<Validated>(None) else {
+        panic!("expected a struct");
+    };
+    let constraints = decl
+        .fields
+        .iter()
+        .map(|field| {
+            let Constraints { min, max, pattern } = &field.constraints;
+            (field.name.as_ref(), *min, *max, pattern.is_some())
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        constraints,
+        [
+            ("name", Some(1.0), Some(10.0), false),
+            ("code", Some(3.0), Some(3.0), false),
+            ("ratio", Some(-1.5), Some(2.5), false),
+            ("percent", None, Some(100.0), false),
+            ("email", None, None, false),
+            ("homepage", None, None, false),
+            ("custom", None, None, false),
+            ("slug", None, None, false),
+            ("address", None, None, false),
+            ("combined", None, Some(5.0), false),
+        ],
+    );
+
+    let string = || String::reflect(&mut TypeContext::new());
+    let formats = decl
+        .fields
+        .iter()
+        .map(|field| (field.name.as_ref(), field.type_desc.clone()))
+        .collect::<Vec<_>>();
+    assert_eq!(formats[4], ("email", string().with_format(Format::Email)));
+    assert_eq!(formats[5], ("homepage", string().with_format(Format::Uri)));
+    assert_eq!(formats[6], ("custom", string()));
+    assert_eq!(formats[7], ("slug", string()));
+    assert_eq!(formats[8], ("address", Type::Id(id::<Address>())));
+}
+
+#[cfg(all(feature = "time", not(feature = "time-human-readable")))]
+#[test]
+fn time_types_are_tuples() {
+    let mut cx = TypeContext::new();
+    assert_eq!(time::Date::reflect(&mut cx), <(i32, u16)>::reflect(&mut cx),);
+    assert_eq!(
+        time::Time::reflect(&mut cx),
+        <(u8, u8, u8, u32)>::reflect(&mut cx),
+    );
+    assert_eq!(
+        time::PrimitiveDateTime::reflect(&mut cx),
+        <(i32, u16, u8, u8, u8, u32)>::reflect(&mut cx),
+    );
+    assert_eq!(
+        time::OffsetDateTime::reflect(&mut cx),
+        <(i32, u16, u8, u8, u8, u32, i8, i8, i8)>::reflect(&mut cx),
+    );
+    assert_eq!(
+        time::UtcOffset::reflect(&mut cx),
+        <(i8, i8, i8)>::reflect(&mut cx),
+    );
+}